@@ -44,4 +44,5 @@ rm -rf %{buildroot}
 %post
 echo "JCD (Jump Change Directory) has been installed successfully!"
 echo ""
-echo "To enable JCD shell integration: source /usr/bin/jcd_function.sh"
\ No newline at end of file
+echo "To enable JCD shell integration, add this line to ~/.bashrc (or use zsh for ~/.zshrc):"
+echo '  eval "$(jcd init bash)"'
\ No newline at end of file
@@ -1,45 +1,97 @@
 #!/bin/bash
 
 # JCD Shell Function - Enhanced Directory Navigation with Inline Tab Completion
-# Usage: Add "source /path/to/jcd_function.sh" to your ~/.bashrc
+# Usage: Add 'eval "$(jcd init bash)"' (or zsh) to your shell rc file,
+#        or add "source /path/to/jcd_function.sh" to your ~/.bashrc
+
+# Search flags passed through to the binary by jcd and its tab completion;
+# `jcd init` regenerates this line from the binary's own flag table
+_JCD_SEARCH_FLAGS=(-i -s --smart-case -S --case-sensitive -u --unicode -a --ignore-accents --no-unicode -x -f --fuzzy --no-fuzzy -w --acronym --no-acronym -r --regex -g --glob -F --fixed)
+
+_jcd_is_search_flag() {
+    local flag
+    for flag in "${_JCD_SEARCH_FLAGS[@]}"; do
+        [[ "$1" == "$flag" ]] && return 0
+    done
+    return 1
+}
 
 _jcd_print_usage() {
     echo "Usage:"
-    echo "  jcd [-i] <directory_pattern>        - Changes directory according to the pattern"
+    echo "  jcd [flags] <directory_pattern>     - Changes directory according to the pattern"
+    echo
+    echo "flags:"
+    echo "  -i                     # Case-insensitive matching"
+    echo "  -s, --smart-case       # Ignore case unless the pattern has uppercase (default)"
+    echo "  -S, --case-sensitive   # Case-sensitive matching"
+    echo "  -u, --unicode          # Unicode normalization and full case folding (--no-unicode to disable)"
+    echo "  -a, --ignore-accents   # Like -u, and accents are ignored (cafe finds Café)"
+    echo "  -x                     # Bypass ignore patterns"
+    echo "  -f, --fuzzy            # Fuzzy subsequence matching (--no-fuzzy to disable)"
+    echo "  -w, --acronym          # Match word initials, fbs finds foo-bar-service (--no-acronym to disable)"
+    echo "  -r, --regex            # Treat the pattern as a regular expression"
+    echo "  -g, --glob             # Treat the pattern as a shell glob (automatic for * ? [)"
+    echo "  -F, --fixed            # Match the pattern literally, even with * ? ["
     echo
     echo "directory_pattern:"
     echo "  jcd <substring>        # Navigate to directory matching substring"
     echo "  jcd <absolute_path>    # Navigate to absolute path"
     echo "  jcd <path/pattern>     # Navigate using path-like patterns"
+    echo "  jcd <term> <term>...   # Every term in path order, the last one in the directory name"
+    echo "  jcd @<name>[/pattern]  # Navigate to a bookmark, or search below it"
+    echo
+    echo "bookmarks:"
+    echo "  jcd --mark <name>      # Bookmark the current directory"
+    echo "  jcd --marks            # List bookmarks"
+    echo "  jcd --unmark <name>    # Delete a bookmark"
+    echo
+    echo "  jcd --explain [flags] <directory_pattern>  # Show how every candidate was ranked or dropped"
+    echo
+    echo "directory index:"
+    echo "  jcd index build [dir]  # Index the directories below dir (default: the current one)"
+    echo "  jcd index status       # List indexes and how much changed since they were built"
+    echo "  jcd index clear [dir]  # Delete the index of dir, or every index"
+    echo "  jcd daemon watch [dir...]  # Keep the directories below each dir current for searches"
+}
+
+# Count a visit in the frecency store after a successful jump; JCD_RECORD=0 opts out
+_jcd_record_visit() {
+    [ "${JCD_RECORD:-1}" = "0" ] && return 0
+    "$1" --record "$PWD" >/dev/null 2>&1
+    return 0
 }
 
 jcd() {
-    # Parse arguments to handle flags
-    local case_insensitive=false
-    local search_term=""
+    # Bookmark commands, --explain, index and daemon commands go straight to the binary
+    case "$1" in
+        --mark|--marks|--unmark|--explain)
+            "${JCD_BINARY:-/usr/bin/jcd}" "$@"
+            return $?
+            ;;
+    esac
+    if [[ "$1" == "index" && "$2" =~ ^(build|status|clear)$ ]] || [[ "$1" == "daemon" && "$2" == "watch" ]]; then
+        "${JCD_BINARY:-/usr/bin/jcd}" "$@"
+        return $?
+    fi
+
+    # Parse arguments to handle flags; several terms form a multi-term query
+    local flags=()
+    local terms=()
 
     while [[ $# -gt 0 ]]; do
-        case $1 in
-            -i)
-                case_insensitive=true
-                shift
-                ;;
-            *)
-                if [ -z "$search_term" ]; then
-                    search_term="$1"
-                else
-                    _jcd_print_usage
-                    return 1
-                fi
-                shift
-                ;;
-        esac
+        if _jcd_is_search_flag "$1"; then
+            flags+=("$1")
+        else
+            terms+=("$1")
+        fi
+        shift
     done
 
-    if [ -z "$search_term" ]; then
+    if [ ${#terms[@]} -eq 0 ] || [ -z "${terms[0]}" ]; then
         _jcd_print_usage
         return 1
     fi
+    local search_term="${terms[*]}"
 
     local jcd_binary="${JCD_BINARY:-/usr/bin/jcd}"
 
@@ -74,27 +126,27 @@ jcd() {
     esac
 
     # Handle trailing slash for Enter - navigate to directory directly
-    if [[ "$search_term" == */ ]]; then
+    if [ ${#terms[@]} -eq 1 ] && [[ "$search_term" == */ ]]; then
         local dir_without_slash="${search_term%/}"
         if [[ -d "$dir_without_slash" ]]; then
-            cd "$dir_without_slash"
-            return $?
+            cd "$dir_without_slash" || return $?
+            _jcd_record_visit "$jcd_binary"
+            return 0
         fi
         # If directory doesn't exist, fall through to search logic
     fi
 
-    # Get the best match (index 0)
-    local dest
-    if [ "$case_insensitive" = true ]; then
-        dest=$("$jcd_binary" -i "$search_term" 0)
-    else
-        dest=$("$jcd_binary" "$search_term" 0)
-    fi
-    if [ $? -ne 0 ] || [ -z "$dest" ]; then
+    # Get the best match (index 0) as a shell-quoted word built from the raw path bytes,
+    # so directories with newlines or non-UTF-8 names round-trip exactly
+    local quoted dest
+    quoted=$("$jcd_binary" "${flags[@]}" "${terms[@]}" --index 0 --shell-quote)
+    if [ $? -ne 0 ] || [ -z "$quoted" ]; then
         echo "No directories found matching '$search_term'"
         return 1
     fi
-    cd "$dest"
+    eval "dest=$quoted"
+    cd -- "$dest" || return $?
+    _jcd_record_visit "$jcd_binary"
 }
 
 # Global variables to store completion state
@@ -322,9 +374,8 @@ _jcd_run_with_animation() {
     _jcd_show_tab_busy_indicator &
     local animation_pid=$!
 
-    # run the real work
-    local output
-    output=$("$@")
+    # run the real work, streaming its output so NUL separators survive
+    "$@"
     local exit_code=$?
 
     # stop the spinner
@@ -334,21 +385,20 @@ _jcd_run_with_animation() {
     # Restore cursor position not supported on Mac
     tput rc >&2
 
-    # emit the actual output to the caller
-    echo "$output"
     return $exit_code
 }
 
 # Get all matches for a relative pattern
 _jcd_get_relative_matches() {
     local pattern="$1"
-    local case_insensitive="$2"  # true/false
+    shift
+    local search_flags=("$@")  # flags from _JCD_SEARCH_FLAGS
     local jcd_binary="${JCD_BINARY:-/usr/bin/jcd}"
     local matches=()
     local idx=0
     local match
 
-    _jcd_debug "getting relative matches for pattern '$pattern' (case_insensitive=$case_insensitive)"
+    _jcd_debug "getting relative matches for pattern '$pattern' (flags: ${search_flags[*]})"
 
     # Detect if we are on macOS (Darwin) or Linux
     local is_darwin=0
@@ -390,84 +440,15 @@ _jcd_get_relative_matches() {
             _jcd_debug "  current directory match: '$PWD'"
             ;;
         *)
-            # Handle relative patterns that contain navigation and search terms
-            if [[ "$pattern" == ../* ]] && [[ "$pattern" != "../.." ]] && [[ "$pattern" != "../../.." ]]; then
-                # Pattern like "../foo" - resolve the relative part and search
-                local resolved_dir="$PWD"
-                local nav_part="${pattern%%[^./]*}"  # Gets "../" or "../../" etc.
-                local search_part="${pattern#$nav_part}"  # Gets the search term after navigation
-
-                _jcd_debug "  split pattern: nav='$nav_part' search='$search_part'"
-
-                # Navigate to the relative directory
-                local nav_count=$(echo "$nav_part" | grep -o "\\.\\." | wc -l)
-                for ((i=0; i<nav_count; i++)); do
-                    resolved_dir="$(dirname "$resolved_dir")"
-                    if [[ "$resolved_dir" == "/" ]]; then
-                        break
-                    fi
-                done
-
-                _jcd_debug "  resolved base directory: '$resolved_dir'"
-
-                if [[ -d "$resolved_dir" ]] && [[ -n "$search_part" ]]; then
-                    # Portable find/sort for Linux and macOS
-                    if command -v gfind >/dev/null 2>&1; then
-                        find_cmd="gfind"
-                    else
-                        find_cmd="find"
-                    fi
-                    if [[ "$(uname)" == "Darwin" ]]; then
-                        # BSD find/sort (macOS)
-                        _jcd_debug "find_cmd: $find_cmd"
-                        _jcd_debug "resolved_dir: $resolved_dir"
-                        _jcd_debug "search_part: $search_part"
-                        _jcd_debug "find command: $find_cmd \"$resolved_dir\" -maxdepth 1 -type d ! -path \"$resolved_dir\" 2>/dev/null | sort"
-                        $find_cmd "$resolved_dir" -maxdepth 1 -type d ! -path "$resolved_dir" 2>/dev/null | sort >&2
-                        while IFS= read -r dir; do
-                            _jcd_debug "  find result: $dir"
-                            if [[ -d "$dir" ]]; then
-                                local dir_name="$(basename "$dir")"
-                                if [[ "$dir_name" == *"$search_part"* ]]; then
-                                    matches+=("$dir")
-                                    _jcd_debug "    found relative pattern match: '$dir'"
-                                fi
-                            fi
-
-                        done < <($find_cmd "$resolved_dir" -maxdepth 1 -type d ! -path "$resolved_dir" 2>/dev/null | sort)
-                    else
-                        # GNU find/sort (Linux)
-                        while IFS= read -r -d $'\0' dir; do
-                            if [[ -d "$dir" ]]; then
-                                local dir_name="$(basename "$dir")"
-                                if [[ "$dir_name" == *"$search_part"* ]]; then
-                                    matches+=("$dir")
-                                    _jcd_debug "    found relative pattern match: '$dir'"
-                                fi
-                            fi
-                        done < <($find_cmd "$resolved_dir" -maxdepth 1 -type d ! -path "$resolved_dir" -print0 2>/dev/null | sort -z)
-                    fi
-                fi
-            else
-                # Use the jcd binary directly, no per-call animation
-                while true; do
-                    if [ "$case_insensitive" = "true" ]; then
-                        match=$("$jcd_binary" -i "$pattern" "$idx" --quiet 2>/dev/null)
-                    else
-                        match=$("$jcd_binary" "$pattern" "$idx" --quiet 2>/dev/null)
-                    fi
-                    if [ $? -ne 0 ] || [ -z "$match" ]; then
-                        break
-                    fi
-                    _jcd_debug "  relative match #$idx: '$match'"
-                    matches+=("$match")
-                    idx=$((idx + 1))
-                    # Safety limit to prevent infinite loops
-                    if [ $idx -gt 100 ]; then
-                        break
-                    fi
-                done
-            fi
+            # Use the jcd binary directly, no per-call animation. "../foo" goes there too, so
+            # completion follows the same case policy and ranking as the jump itself.
+            # A single --list invocation returns every ranked match, NUL-separated
+            # so that directory names containing newlines survive intact.
+            while IFS= read -r -d '' match; do
+                _jcd_debug "  relative match #$idx: '$match'"
+                matches+=("$match")
+                idx=$((idx + 1))
+            done < <("$jcd_binary" "${search_flags[@]}" "$pattern" --list -0 --quiet 2>/dev/null)
             ;;
     esac
 
@@ -476,34 +457,30 @@ _jcd_get_relative_matches() {
         _jcd_debug "returning empty result (no printf output)"
         return 0
     fi
-    printf '%s\n' "${matches[@]}"
+    printf '%s\0' "${matches[@]}"
 }
 
 # Get all matches for an absolute pattern
 _jcd_get_absolute_matches() {
     local pattern="$1"
-    local case_insensitive="$2"  # true/false
+    shift
+    local search_flags=("$@")  # flags from _JCD_SEARCH_FLAGS
     local jcd_binary="${JCD_BINARY:-/usr/bin/jcd}"
     local matches=()
 
-    _jcd_debug "getting absolute matches for pattern '$pattern' (case_insensitive=$case_insensitive)"
+    _jcd_debug "getting absolute matches for pattern '$pattern' (flags: ${search_flags[*]})"
     # Handle relative path patterns that start with ../
     if [[ "$pattern" == ../* ]]; then
         _jcd_debug "  pattern starts with ../, using relative match logic"
-        local match_output
-        match_output=$(_jcd_get_relative_matches "$pattern" "$case_insensitive")
-        if [[ -n "$match_output" ]]; then
-            matches=()
-            while IFS= read -r line; do
-                matches+=("$line")
-            done <<<"$match_output"
-        fi
+        while IFS= read -r -d '' line; do
+            matches+=("$line")
+        done < <(_jcd_get_relative_matches "$pattern" "${search_flags[@]}")
         _jcd_debug "found ${#matches[@]} matches via relative logic"
         if [ ${#matches[@]} -eq 0 ]; then
             _jcd_debug "returning empty result (no printf output)"
             return 0
         fi
-        printf '%s\n' "${matches[@]}"
+        printf '%s\0' "${matches[@]}"
         return 0
     fi
 
@@ -514,30 +491,18 @@ _jcd_get_absolute_matches() {
 
     _jcd_debug "using jcd binary for absolute pattern '$pattern'"
 
-    while true; do
-        if [ "$case_insensitive" = "true" ]; then
-            match=$("$jcd_binary" -i "$pattern" "$idx" --quiet 2>/dev/null)
-        else
-            match=$("$jcd_binary" "$pattern" "$idx" --quiet 2>/dev/null)
-        fi
-        if [ $? -ne 0 ] || [ -z "$match" ]; then
-            break
-        fi
+    while IFS= read -r -d '' match; do
         _jcd_debug "  absolute match #$idx: '$match'"
         matches+=("$match")
         idx=$((idx + 1))
-        # Safety limit to prevent infinite loops
-        if [ $idx -gt 100 ]; then
-            break
-        fi
-    done
+    done < <("$jcd_binary" "${search_flags[@]}" "$pattern" --list -0 --quiet 2>/dev/null)
 
     _jcd_debug "found ${#matches[@]} absolute matches via binary"
     if [ ${#matches[@]} -eq 0 ]; then
         _jcd_debug "returning empty result (no printf output)"
         return 0
     fi
-    printf '%s\n' "${matches[@]}"
+    printf '%s\0' "${matches[@]}"
 }
 
 # Find current position in match array (handle trailing slash variations)
@@ -576,15 +541,14 @@ _jcd_backward_tab_complete() {
 
 # Internal tab completion function that handles both directions
 _jcd_tab_complete_internal() {
-    # Parse arguments to find -i flag and determine what we're completing
-    local has_i_flag=false
+    # Collect leading search flags and determine what we're completing
+    local search_flags=()
     local pattern_index=1
 
-    # Check if -i flag is present
-    if [[ ${#COMP_WORDS[@]} -gt 1 ]] && [[ "${COMP_WORDS[1]}" == "-i" ]]; then
-        has_i_flag=true
-        pattern_index=2
-    fi
+    while [[ $pattern_index -lt ${#COMP_WORDS[@]} ]] && _jcd_is_search_flag "${COMP_WORDS[$pattern_index]}"; do
+        search_flags+=("${COMP_WORDS[$pattern_index]}")
+        pattern_index=$((pattern_index + 1))
+    done
 
     local cur="${COMP_WORDS[@]:${pattern_index}:${COMP_CWORD}}"
     local prev="${COMP_WORDS[@]:${pattern_index}:${COMP_CWORD}}"
@@ -594,7 +558,7 @@ _jcd_tab_complete_internal() {
     _jcd_debug "cur='$cur' prev='$prev' COMP_CWORD=$COMP_CWORD pattern_index=$pattern_index"
     _jcd_debug "full command: ${COMP_WORDS[*]}"
 
-    # Only complete the pattern argument (could be at index 1 or 2 depending on -i flag)
+    # Only complete the pattern argument, which follows any leading search flags
     if [ $COMP_CWORD -ne $pattern_index ]; then
         _jcd_debug "not completing pattern argument (COMP_CWORD=$COMP_CWORD, pattern_index=$pattern_index), returning"
         return 0
@@ -629,13 +593,11 @@ _jcd_tab_complete_internal() {
                 # Set up for parent directory exploration
                 _JCD_ORIGINAL_PATTERN="$parent_dir/"
                 _JCD_IS_RELATIVE_PATTERN=false
-                local match_output
-                match_output=$(_jcd_get_absolute_matches "$parent_dir/" "false")
-                if [[ -n "$match_output" ]]; then
-                    _JCD_CURRENT_MATCHES=()
-                    while IFS= read -r line; do
-                        _JCD_CURRENT_MATCHES+=("$line")
-                    done <<<"$match_output"
+                _JCD_CURRENT_MATCHES=()
+                while IFS= read -r -d '' line; do
+                    _JCD_CURRENT_MATCHES+=("$line")
+                done < <(_jcd_get_absolute_matches "$parent_dir/")
+                if [[ ${#_JCD_CURRENT_MATCHES[@]} -gt 0 ]]; then
                     _JCD_COMPLETION_MODE="cycling"
                     _JCD_CURRENT_INDEX=0
                     _jcd_debug "reset to parent directory cycling with ${#_JCD_CURRENT_MATCHES[@]} matches"
@@ -678,52 +640,31 @@ _jcd_tab_complete_internal() {
             _jcd_debug "treating '$cur' as absolute pattern"
             _JCD_ORIGINAL_PATTERN="$cur"
             _JCD_IS_RELATIVE_PATTERN=false
-            local match_output
-            match_output=$(_jcd_run_with_animation _jcd_get_absolute_matches "$cur" "$has_i_flag")
-            _jcd_debug "raw match output: '$match_output'"
-            if [[ -n "$match_output" ]]; then
-                _JCD_CURRENT_MATCHES=()
-                while IFS= read -r line; do
-                    _JCD_CURRENT_MATCHES+=("$line")
-                done <<<"$match_output"
-            else
-                _JCD_CURRENT_MATCHES=()
-                _jcd_debug "explicitly set empty array for empty match output"
-            fi
+            _JCD_CURRENT_MATCHES=()
+            while IFS= read -r -d '' line; do
+                _JCD_CURRENT_MATCHES+=("$line")
+            done < <(_jcd_run_with_animation _jcd_get_absolute_matches "$cur" "${search_flags[@]}")
+            _jcd_debug "read ${#_JCD_CURRENT_MATCHES[@]} matches"
         elif [[ "$cur" == ../* ]] || [[ "$cur" == ./* ]] || [[ "$cur" == "." ]] || [[ "$cur" == ".." ]]; then
             # Relative pattern (including ../, ./, ., ..)
             _jcd_debug "treating '$cur' as relative pattern"
             _JCD_ORIGINAL_PATTERN="$cur"
             _JCD_IS_RELATIVE_PATTERN=true
-            local match_output
-            match_output=$(_jcd_run_with_animation _jcd_get_relative_matches "$cur" "$has_i_flag")
-            _jcd_debug "raw match output: '$match_output'"
-            if [[ -n "$match_output" ]]; then
-                _JCD_CURRENT_MATCHES=()
-                while IFS= read -r line; do
-                    _JCD_CURRENT_MATCHES+=("$line")
-                done <<<"$match_output"
-            else
-                _JCD_CURRENT_MATCHES=()
-                _jcd_debug "explicitly set empty array for empty match output"
-            fi
+            _JCD_CURRENT_MATCHES=()
+            while IFS= read -r -d '' line; do
+                _JCD_CURRENT_MATCHES+=("$line")
+            done < <(_jcd_run_with_animation _jcd_get_relative_matches "$cur" "${search_flags[@]}")
+            _jcd_debug "read ${#_JCD_CURRENT_MATCHES[@]} matches"
         else
             # Regular relative pattern (no explicit path prefix)
             _jcd_debug "treating '$cur' as regular relative pattern"
             _JCD_ORIGINAL_PATTERN="$cur"
             _JCD_IS_RELATIVE_PATTERN=true
-            local match_output
-            match_output=$(_jcd_run_with_animation _jcd_get_relative_matches "$cur" "$has_i_flag")
-            _jcd_debug "raw match output: '$match_output'"
-            if [[ -n "$match_output" ]]; then
-                _JCD_CURRENT_MATCHES=()
-                while IFS= read -r line; do
-                    _JCD_CURRENT_MATCHES+=("$line")
-                done <<<"$match_output"
-            else
-                _JCD_CURRENT_MATCHES=()
-                _jcd_debug "explicitly set empty array for empty match output"
-            fi
+            _JCD_CURRENT_MATCHES=()
+            while IFS= read -r -d '' line; do
+                _JCD_CURRENT_MATCHES+=("$line")
+            done < <(_jcd_run_with_animation _jcd_get_relative_matches "$cur" "${search_flags[@]}")
+            _jcd_debug "read ${#_JCD_CURRENT_MATCHES[@]} matches"
         fi
 
         # Don't override completion mode if we're already in leaf mode
@@ -879,40 +820,33 @@ _jcd_shift_tab_handler() {
     local point="$READLINE_POINT"
 
     # Check if we're at the end of a jcd command
-    if [[ "$line" =~ ^jcd[[:space:]]+((-i[[:space:]]+)?[^[:space:]]*)?$ ]]; then
-        # Extract the current word being completed
+    if [[ "$line" =~ ^jcd([[:space:]]+[^[:space:]]*)*$ ]]; then
         local words
         read -ra words <<< "$line"
-        local cur=""
-
-        # Determine what we're completing
-        if [[ ${#words[@]} -gt 1 ]]; then
-            if [[ "${words[1]}" == "-i" ]] && [[ ${#words[@]} -gt 2 ]]; then
-                cur="${words[2]}"
-            elif [[ "${words[1]}" != "-i" ]]; then
-                cur="${words[1]}"
-            fi
+
+        # Leading search flags are kept on the line; the word after them is the pattern
+        local prefix="jcd"
+        local pattern_index=1
+        while [[ $pattern_index -lt ${#words[@]} ]] && _jcd_is_search_flag "${words[$pattern_index]}"; do
+            prefix+=" ${words[$pattern_index]}"
+            pattern_index=$((pattern_index + 1))
+        done
+
+        # Only the pattern itself is completed
+        if [[ ${#words[@]} -gt $((pattern_index + 1)) ]]; then
+            return
         fi
 
         # Set up COMP_WORDS and COMP_CWORD for the completion function
         COMP_WORDS=("${words[@]}")
-        if [[ "${words[1]}" == "-i" ]]; then
-            COMP_CWORD=2
-        else
-            COMP_CWORD=1
-        fi
+        COMP_CWORD=$pattern_index
 
         # Call backward tab completion
         _jcd_backward_tab_complete
 
         # Replace the current line with the completion
         if [[ ${#COMPREPLY[@]} -gt 0 ]]; then
-            local completion="${COMPREPLY[0]}"
-            if [[ "${words[1]}" == "-i" ]]; then
-                READLINE_LINE="jcd -i $completion"
-            else
-                READLINE_LINE="jcd $completion"
-            fi
+            READLINE_LINE="$prefix ${COMPREPLY[0]}"
             READLINE_POINT=${#READLINE_LINE}
         fi
     fi
@@ -946,7 +880,9 @@ elif [[ -n "${ZSH_VERSION:-}" ]]; then
 
         local state line
         _arguments \
-            '(-i)--case-insensitive[Case insensitive search]' \
+            '(-s --smart-case -S --case-sensitive)-i[Case-insensitive matching]' \
+            '(-i -S --case-sensitive)'{-s,--smart-case}'[Ignore case unless the pattern has uppercase]' \
+            '(-i -s --smart-case)'{-S,--case-sensitive}'[Case-sensitive matching]' \
             '*:directory pattern:->pattern'
 
         setopt KSH_ARRAYS
@@ -955,14 +891,8 @@ elif [[ -n "${ZSH_VERSION:-}" ]]; then
             pattern)
                 # Get completions using our existing bash logic
                 local cur="${words[CURRENT]}"
-                local has_i_flag=false
-
-                # Check for -i flag
-                if [[ "${words[*]}" == *" -i "* ]]; then
-                    has_i_flag=true
-                fi
 
-                # Use our existing completion logic
+                # Use our existing completion logic; it passes leading search flags such as -i through
                 typeset -a COMP_WORDS
                 for ((i=1; i<=$#words-1; i++)); do
                     COMP_WORDS[i-1]="${words[i]}"
@@ -1021,4 +951,5 @@ elif [[ -n "${ZSH_VERSION:-}" ]]; then
 else
     echo "Warning: JCD completion is only supported in bash and zsh. Current shell: ${SHELL:-unknown}" >&2
     echo "The jcd function will work, but tab completion will not be available." >&2
+    echo "For fish, use: jcd init fish | source" >&2
 fi
\ No newline at end of file
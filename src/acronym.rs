@@ -0,0 +1,53 @@
+// Acronym matching: each pattern character must match the first character of a word
+// segment, in order, so `fbs` finds `foo-bar-service` and `FooBarService`. Segments start
+// at the beginning of the name, after `-`, `_` or `.`, where digits begin or end, and at
+// lower-to-upper case transitions.
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '-' | '_' | '.')
+}
+
+/// Whether a new word segment starts at `current` when it follows `previous`
+fn starts_segment(previous: Option<char>, current: char) -> bool {
+    if is_separator(current) {
+        return false;
+    }
+    match previous {
+        None => true,
+        Some(previous) => {
+            is_separator(previous)
+                || previous.is_numeric() != current.is_numeric()
+                || (previous.is_lowercase() && current.is_uppercase())
+        }
+    }
+}
+
+fn chars_equal(a: char, b: char, case_sensitive: bool) -> bool {
+    a == b || (!case_sensitive && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+/// Match `pattern` against the segment initials of `name`.
+/// Returns the byte offsets in `name` of the initials that matched, one per pattern character.
+pub fn acronym_match(name: &str, pattern: &str, case_sensitive: bool) -> Option<Vec<usize>> {
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let mut initials = Vec::new();
+    let mut previous = None;
+    for (offset, c) in name.char_indices() {
+        if starts_segment(previous, c) {
+            initials.push((offset, c));
+        }
+        previous = Some(c);
+    }
+
+    // Taking the earliest initial for each pattern character never rules out a later match
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut remaining = initials.iter();
+    for pc in pattern.chars() {
+        let &(offset, _) = remaining.find(|&&(_, c)| chars_equal(c, pc, case_sensitive))?;
+        positions.push(offset);
+    }
+    Some(positions)
+}
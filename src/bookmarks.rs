@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{config_home, is_debug_enabled, path_bytes, path_from_bytes};
+
+/// Named directories stored under `$XDG_CONFIG_HOME/jcd/bookmarks`, next to the ignore file
+#[derive(Debug, Default)]
+pub struct BookmarkStore {
+    file: PathBuf,
+    marks: Vec<(String, PathBuf)>,
+}
+
+/// Bookmark names are used in `@name/sub` patterns, so they cannot contain separators
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+impl BookmarkStore {
+    /// Load bookmarks from their default location; a missing or unreadable file is empty
+    pub fn load() -> Self {
+        Self::load_from(config_home().join("jcd").join("bookmarks"))
+    }
+
+    pub fn load_from(file: PathBuf) -> Self {
+        let mut store = BookmarkStore {
+            file,
+            marks: Vec::new(),
+        };
+
+        let content = match fs::read(&store.file) {
+            Ok(content) => content,
+            Err(_) => {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: No bookmark file at {}", store.file.display());
+                }
+                return store;
+            }
+        };
+
+        for line in content.split(|&b| b == b'\n') {
+            // Each line is "<name>\t<path bytes>"
+            let mut fields = line.splitn(2, |&b| b == b'\t');
+            let (Some(name), Some(path)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let Ok(name) = std::str::from_utf8(name) else {
+                continue;
+            };
+            if is_valid_name(name) && !path.is_empty() {
+                store.marks.push((name.to_string(), path_from_bytes(path)));
+            }
+        }
+
+        // The file may have been edited by hand
+        store.marks.sort_by(|a, b| a.0.cmp(&b.0));
+        store.marks.dedup_by(|a, b| a.0 == b.0);
+
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Loaded {} bookmarks from {}",
+                store.marks.len(),
+                store.file.display()
+            );
+        }
+        store
+    }
+
+    /// All bookmarks sorted by name
+    pub fn marks(&self) -> &[(String, PathBuf)] {
+        &self.marks
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Path> {
+        self.marks
+            .iter()
+            .find(|(mark, _)| mark == name)
+            .map(|(_, path)| path.as_path())
+    }
+
+    /// Add or replace a bookmark
+    pub fn set(&mut self, name: &str, dir: &Path) {
+        match self.marks.iter_mut().find(|(mark, _)| mark == name) {
+            Some((_, path)) => *path = dir.to_path_buf(),
+            None => self.marks.push((name.to_string(), dir.to_path_buf())),
+        }
+        self.marks.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
+    /// Remove a bookmark, returning false if it did not exist
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.marks.len();
+        self.marks.retain(|(mark, _)| mark != name);
+        self.marks.len() != before
+    }
+
+    /// Write the bookmarks atomically so concurrent shells never see a partial file
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = Vec::new();
+        for (name, path) in &self.marks {
+            let path = path_bytes(path);
+            if path.contains(&b'\n') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "directories with newlines in their path cannot be bookmarked",
+                ));
+            }
+            write!(content, "{}\t", name)?;
+            content.extend_from_slice(&path);
+            content.push(b'\n');
+        }
+
+        let tmp = self
+            .file
+            .with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &self.file)
+    }
+}
@@ -0,0 +1,158 @@
+// Search budgets. Every search strategy takes its limits from one `SearchBudget`: how far
+// down and up it looks, and how many results, directories and milliseconds it may spend.
+// The depth limits only shape the search; when one of the other budgets runs out the search
+// stops where it is, and the cut is noted so jcd can say the results may be incomplete.
+
+use std::time::Duration;
+
+const MAX_UP: usize = 10; // Ancestors checked by default
+const MAX_MATCHES: usize = 20; // Stop after finding enough matches
+const MAX_SEARCH_TIME_MS: u64 = 500; // Max time to spend searching (milliseconds)
+
+/// Budget names, each settable as `--max-<name>`, `JCD_MAX_<NAME>` and `search.<name>`
+pub const NAMES: [&str; 6] = ["depth", "path-depth", "up", "results", "dirs", "time"];
+
+#[derive(Debug, Clone)]
+pub struct SearchBudget {
+    pub depth: usize,           // Levels searched below the starting directory
+    pub path_depth: usize,      // Levels searched for the first part of a path pattern
+    pub up: Option<usize>,      // Ancestors checked; `None` walks up to the root
+    pub results: Option<usize>, // Matches collected before the search stops
+    pub dirs: Option<usize>,    // Directories read before the search stops
+    pub time: Option<Duration>, // Time spent before the search stops
+}
+
+impl Default for SearchBudget {
+    fn default() -> Self {
+        SearchBudget {
+            depth: 8,
+            path_depth: 4,
+            up: Some(MAX_UP),
+            results: Some(MAX_MATCHES),
+            dirs: None,
+            time: Some(Duration::from_millis(MAX_SEARCH_TIME_MS)),
+        }
+    }
+}
+
+/// A budget that stopped a search before it was done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Results,
+    Dirs,
+    Time,
+}
+
+impl SearchBudget {
+    /// Set the budget called `name` from a count, milliseconds for `time`, or `none` for no
+    /// limit; returns false if the name or value is not understood
+    pub fn set(&mut self, name: &str, value: &str) -> bool {
+        let limit = if value == "none" {
+            None
+        } else {
+            match value.parse::<usize>() {
+                Ok(limit) => Some(limit),
+                Err(_) => return false,
+            }
+        };
+        match (name, limit) {
+            ("depth", Some(depth)) => self.depth = depth,
+            ("path-depth", Some(depth)) => self.path_depth = depth,
+            ("up", limit) => self.up = limit,
+            ("results", limit) => self.results = limit,
+            ("dirs", limit) => self.dirs = limit,
+            ("time", limit) => self.time = limit.map(|ms| Duration::from_millis(ms as u64)),
+            _ => return false,
+        }
+        true
+    }
+
+    /// The budget behind `limit`, as named in the warning after a search it cut short
+    pub fn describe(&self, limit: Limit) -> String {
+        match limit {
+            Limit::Results => format!("the result limit ({})", self.results.unwrap_or_default()),
+            Limit::Dirs => format!("the directory limit ({})", self.dirs.unwrap_or_default()),
+            Limit::Time => format!(
+                "the time limit ({} ms)",
+                self.time.unwrap_or_default().as_millis()
+            ),
+        }
+    }
+}
+
+impl Limit {
+    /// The flag that raises this budget
+    pub fn flag(self) -> &'static str {
+        match self {
+            Limit::Results => "--max-results",
+            Limit::Dirs => "--max-dirs",
+            Limit::Time => "--max-time",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_parses_counts_and_milliseconds() {
+        let mut budget = SearchBudget::default();
+        assert!(budget.set("depth", "3"));
+        assert!(budget.set("path-depth", "2"));
+        assert!(budget.set("results", "5"));
+        assert!(budget.set("time", "250"));
+        assert_eq!(budget.depth, 3);
+        assert_eq!(budget.path_depth, 2);
+        assert_eq!(budget.results, Some(5));
+        assert_eq!(budget.time, Some(Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn none_lifts_a_limit() {
+        let mut budget = SearchBudget::default();
+        for name in ["up", "results", "dirs", "time"] {
+            assert!(budget.set(name, "none"), "{}", name);
+        }
+        assert_eq!(budget.up, None);
+        assert_eq!(budget.results, None);
+        assert_eq!(budget.dirs, None);
+        assert_eq!(budget.time, None);
+    }
+
+    #[test]
+    fn depths_always_have_a_limit() {
+        let mut budget = SearchBudget::default();
+        assert!(!budget.set("depth", "none"));
+        assert!(!budget.set("path-depth", "none"));
+        assert_eq!(budget.depth, SearchBudget::default().depth);
+    }
+
+    #[test]
+    fn set_rejects_unknown_names_and_values() {
+        let mut budget = SearchBudget::default();
+        assert!(!budget.set("width", "3"));
+        assert!(!budget.set("results", "-1"));
+        assert!(!budget.set("time", "1s"));
+        assert!(!budget.set("dirs", ""));
+        assert_eq!(budget.results, SearchBudget::default().results);
+    }
+
+    #[test]
+    fn every_name_can_be_set() {
+        let mut budget = SearchBudget::default();
+        for name in NAMES {
+            assert!(budget.set(name, "1"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn limits_name_their_budget_and_flag() {
+        let mut budget = SearchBudget::default();
+        budget.set("results", "20");
+        budget.set("time", "500");
+        assert_eq!(budget.describe(Limit::Results), "the result limit (20)");
+        assert_eq!(budget.describe(Limit::Time), "the time limit (500 ms)");
+        assert_eq!(Limit::Dirs.flag(), "--max-dirs");
+    }
+}
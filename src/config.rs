@@ -0,0 +1,146 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    budget::SearchBudget, config_home, is_debug_enabled, normalize::Normalization,
+    ranking::Ranking, CasePolicy,
+};
+
+/// User defaults read from `$XDG_CONFIG_HOME/jcd/config`, next to the ignore file.
+/// Each line is `key = value`; blank lines and `#` comments, including trailing ones, are skipped.
+/// A `#` starts a comment only at the start of a line or after whitespace, so `~/src/c#` is a value.
+#[derive(Debug, Default)]
+pub struct Config {
+    pub case: Option<CasePolicy>, // case = smart | sensitive | insensitive
+    pub normalize: Option<Normalization>, // normalize = none | unicode | ignore-accents
+    pub ranking: Ranking,         // rank.<weight> = buckets, rank.tiebreak = keys
+    pub threads: Option<usize>,   // threads = directory reads in parallel, 0 for one per CPU
+    pub budget: SearchBudget,     // search.<budget> = limit, or none
+    pub daemon_roots: Vec<PathBuf>, // daemon.root = directory, once per root `jcd daemon` watches
+}
+
+impl Config {
+    /// Load the config from its default location; a missing file leaves every default in place
+    pub fn load() -> Self {
+        Self::load_from(config_home().join("jcd").join("config"))
+    }
+
+    pub fn load_from(file: PathBuf) -> Self {
+        let mut config = Config::default();
+
+        let Ok(content) = fs::read_to_string(&file) else {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: No config file at {}", file.display());
+            }
+            return config;
+        };
+
+        for line in content.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: Ignoring config line without '=': '{}'", line);
+                }
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "case" => match CasePolicy::parse(value) {
+                    Some(policy) => config.case = Some(policy),
+                    None => {
+                        if is_debug_enabled() {
+                            eprintln!("DEBUG: Unknown case policy '{}' in config", value);
+                        }
+                    }
+                },
+                "normalize" => match Normalization::parse(value) {
+                    Some(normalization) => config.normalize = Some(normalization),
+                    None => {
+                        if is_debug_enabled() {
+                            eprintln!("DEBUG: Unknown normalization '{}' in config", value);
+                        }
+                    }
+                },
+                "threads" => match value.parse() {
+                    Ok(threads) => config.threads = Some(threads),
+                    Err(_) => {
+                        if is_debug_enabled() {
+                            eprintln!("DEBUG: Invalid thread count '{}' in config", value);
+                        }
+                    }
+                },
+                "daemon.root" => config.daemon_roots.push(PathBuf::from(value)),
+                _ if key.starts_with("search.") => {
+                    if !config.budget.set(&key["search.".len()..], value) && is_debug_enabled() {
+                        eprintln!("DEBUG: Ignoring search budget '{} = {}'", key, value);
+                    }
+                }
+                _ if key.starts_with("rank.") => {
+                    if !config.ranking.set(&key["rank.".len()..], value) && is_debug_enabled() {
+                        eprintln!("DEBUG: Ignoring ranking setting '{} = {}'", key, value);
+                    }
+                }
+                _ => {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: Ignoring unknown config key '{}'", key);
+                    }
+                }
+            }
+        }
+
+        config
+    }
+}
+
+/// The part of `line` before its comment, which starts at a `#` that opens the line or follows whitespace
+fn strip_comment(line: &str) -> &str {
+    let comment = line.char_indices().find(|&(i, c)| {
+        c == '#'
+            && line[..i]
+                .chars()
+                .next_back()
+                .is_none_or(char::is_whitespace)
+    });
+    match comment {
+        Some((i, _)) => &line[..i],
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_start_a_line_or_follow_whitespace() {
+        assert_eq!(strip_comment("# threads = 2"), "");
+        assert_eq!(strip_comment("threads = 2 # two readers"), "threads = 2 ");
+        assert_eq!(strip_comment("threads = 2\t#two readers"), "threads = 2\t");
+        assert_eq!(
+            strip_comment("daemon.root = ~/src/c#"),
+            "daemon.root = ~/src/c#"
+        );
+        assert_eq!(
+            strip_comment("daemon.root = ~/src/c# # notes"),
+            "daemon.root = ~/src/c# "
+        );
+    }
+
+    #[test]
+    fn values_keep_inner_hashes() {
+        let file = std::env::temp_dir().join(format!("jcd-config-test-{}", std::process::id()));
+        fs::write(
+            &file,
+            "# roots\ndaemon.root = /src/c#\ndaemon.root = /src/f# # F sharp\n",
+        )
+        .unwrap();
+        let config = Config::load_from(file.clone());
+        fs::remove_file(&file).unwrap();
+        assert_eq!(
+            config.daemon_roots,
+            [PathBuf::from("/src/c#"), PathBuf::from("/src/f#")]
+        );
+    }
+}
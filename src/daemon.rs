@@ -0,0 +1,479 @@
+// Background indexer. `jcd daemon watch` walks its roots once, puts an inotify watch on every
+// directory it keeps, and re-lists a directory whenever a subdirectory appears in it or goes
+// away, so its directory set stays current without rescanning. Searches ask it for listings
+// over a Unix socket in `$XDG_RUNTIME_DIR`, one round trip per level of the breadth-first
+// walk. A directory the daemon does not watch, or every directory when no daemon is running,
+// is listed from the index or from disk as before.
+//
+// The protocol is line based. A request is the directories of one level separated by NULs;
+// the answer has one line per directory, `+` followed by `\0<name>` for each subdirectory,
+// with a trailing `/` for symlinks, or `?` when the daemon does not watch it.
+
+use std::{
+    env,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+#[cfg(unix)]
+use std::{
+    io::{self, BufRead, BufReader},
+    os::unix::net::UnixStream,
+    sync::Mutex,
+    time::Duration,
+};
+
+#[cfg(target_os = "linux")]
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::BufWriter,
+    os::unix::net::UnixListener,
+    sync::{Arc, RwLock},
+    thread,
+};
+
+#[cfg(target_os = "linux")]
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+
+#[cfg(target_os = "linux")]
+use crate::{ignore::should_ignore_directory, path_from_bytes, walk};
+use crate::{ignore::IgnoreRules, walk::Subdirectory};
+#[cfg(unix)]
+use crate::{is_debug_enabled, path_bytes};
+
+const SOCKET: &str = "jcd.sock";
+
+/// Socket the daemon listens on; `None` without `$XDG_RUNTIME_DIR`
+fn socket_path() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(SOCKET))
+}
+
+/// One search's connection to the running daemon; without a daemon it lists nothing
+#[derive(Debug, Default)]
+pub struct Connection {
+    #[cfg(unix)]
+    stream: Mutex<Option<BufReader<UnixStream>>>,
+}
+
+/// How long a search waits for the daemon before listing directories itself
+#[cfg(unix)]
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+impl Connection {
+    /// Connect to the running daemon, if there is one
+    #[cfg(unix)]
+    pub fn open() -> Self {
+        let connection = Connection::default();
+        let Some(socket) = socket_path() else {
+            return connection;
+        };
+        let Ok(stream) = UnixStream::connect(&socket) else {
+            return connection;
+        };
+        if stream.set_read_timeout(Some(TIMEOUT)).is_err()
+            || stream.set_write_timeout(Some(TIMEOUT)).is_err()
+        {
+            return connection;
+        }
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Listing directories from the daemon at {}",
+                socket.display()
+            );
+        }
+        Connection {
+            stream: Mutex::new(Some(BufReader::new(stream))),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn open() -> Self {
+        Connection::default()
+    }
+
+    /// Subdirectories of each of `dirs` as the daemon has them, in the order of `dirs`; `None`
+    /// for the directories it does not watch, or for all of them when no daemon is connected
+    #[cfg(unix)]
+    pub fn listings(&self, dirs: &[&Path]) -> Vec<Option<Vec<Subdirectory>>> {
+        let mut listed = vec![None; dirs.len()];
+        let Ok(mut connection) = self.stream.lock() else {
+            return listed;
+        };
+        let Some(stream) = connection.as_mut() else {
+            return listed;
+        };
+
+        // Paths with newlines cannot be asked for, so those are listed locally
+        let asked: Vec<usize> = (0..dirs.len())
+            .filter(|&i| !path_bytes(dirs[i]).contains(&b'\n'))
+            .collect();
+        if asked.is_empty() {
+            return listed;
+        }
+        let request: Vec<&Path> = asked.iter().map(|&i| dirs[i]).collect();
+        match ask(stream, &request) {
+            Ok(answers) => {
+                for (i, answer) in asked.into_iter().zip(answers) {
+                    listed[i] = answer;
+                }
+            }
+            Err(e) => {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: Daemon stopped answering: {}", e);
+                }
+                *connection = None;
+            }
+        }
+        listed
+    }
+
+    #[cfg(not(unix))]
+    pub fn listings(&self, dirs: &[&Path]) -> Vec<Option<Vec<Subdirectory>>> {
+        vec![None; dirs.len()]
+    }
+}
+
+#[cfg(unix)]
+fn ask(
+    stream: &mut BufReader<UnixStream>,
+    dirs: &[&Path],
+) -> io::Result<Vec<Option<Vec<Subdirectory>>>> {
+    let mut request = Vec::new();
+    for (i, dir) in dirs.iter().enumerate() {
+        if i > 0 {
+            request.push(0);
+        }
+        request.extend_from_slice(&path_bytes(dir));
+    }
+    request.push(b'\n');
+    stream.get_mut().write_all(&request)?;
+
+    let mut answers = Vec::with_capacity(dirs.len());
+    let mut line = Vec::new();
+    for dir in dirs {
+        line.clear();
+        stream.read_until(b'\n', &mut line)?;
+        if line.pop() != Some(b'\n') {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        answers.push(match line.split_first() {
+            Some((b'+', names)) => Some(
+                names
+                    .split(|&b| b == 0)
+                    .skip(1)
+                    .map(|name| Subdirectory::from_listed_name(dir, name))
+                    .collect(),
+            ),
+            _ => None,
+        });
+    }
+    Ok(answers)
+}
+
+/// Events that can change which subdirectories a watched directory has
+#[cfg(target_os = "linux")]
+const WATCH_MASK: WatchMask = WatchMask::CREATE
+    .union(WatchMask::DELETE)
+    .union(WatchMask::MOVED_FROM)
+    .union(WatchMask::MOVED_TO)
+    .union(WatchMask::ONLYDIR)
+    .union(WatchMask::DONT_FOLLOW);
+
+/// Listings of every watched directory, sorted by name like `walk::read_subdirectories`
+#[cfg(target_os = "linux")]
+type Listings = Arc<RwLock<BTreeMap<PathBuf, Vec<Subdirectory>>>>;
+
+#[cfg(target_os = "linux")]
+struct Watcher {
+    inotify: Inotify,
+    roots: Vec<PathBuf>,
+    ignore_patterns: Vec<IgnoreRules>, // Rooted at each of `roots` in turn
+    listings: Listings,
+    watches: HashMap<WatchDescriptor, PathBuf>,
+    log: Box<dyn Write>, // Where progress and warnings go
+    warned: bool,        // Whether running out of watches was reported
+}
+
+#[cfg(target_os = "linux")]
+impl Watcher {
+    /// Whether the daemon keeps the tree below `dir`; like `jcd index build` it skips ignored
+    /// directories. Symlinks are left out by the callers, which know from the listing or the
+    /// event whether `dir` is one.
+    fn keeps(&self, dir: &Path) -> bool {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy();
+        // Gitignore rules are anchored at the innermost watched root holding `dir`
+        let Some((_, ignore_patterns)) = self
+            .roots
+            .iter()
+            .zip(&self.ignore_patterns)
+            .filter(|(root, _)| dir.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+        else {
+            return true;
+        };
+        !should_ignore_directory(dir, &name, ignore_patterns)
+    }
+
+    /// Watch and list `top` and everything below it that the daemon keeps
+    fn add_tree(&mut self, top: &Path) {
+        let mut pending = vec![top.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            // Watch before listing, so a change made during the listing still gets an event
+            let wd = match self.inotify.watches().add(&dir, WATCH_MASK) {
+                Ok(wd) => wd,
+                Err(e) => {
+                    // Unwatched directories have no listing and are read from disk by searches.
+                    // Directories removed again before they were watched need no warning.
+                    if e.kind() != io::ErrorKind::NotFound && !self.warned {
+                        let _ = writeln!(
+                            self.log,
+                            "jcd: cannot watch {}: {}; directories that cannot be watched are read from disk (see fs.inotify.max_user_watches)",
+                            dir.display(),
+                            e
+                        );
+                        self.warned = true;
+                    }
+                    continue;
+                }
+            };
+            self.watches.insert(wd, dir.clone());
+
+            let children = walk::read_subdirectories(&dir);
+            pending.extend(
+                children
+                    .iter()
+                    .filter(|child| !child.link && self.keeps(&child.path))
+                    .map(|child| child.path.clone()),
+            );
+            if let Ok(mut listings) = self.listings.write() {
+                listings.insert(dir, children);
+            }
+        }
+    }
+
+    /// Forget `top` and everything below it
+    fn remove_tree(&mut self, top: &Path) {
+        let Ok(mut listings) = self.listings.write() else {
+            return;
+        };
+        // A subtree sorts right after its root, so it is one contiguous range
+        let below: Vec<PathBuf> = listings
+            .range(top.to_path_buf()..)
+            .map(|(dir, _)| dir)
+            .take_while(|dir| dir.starts_with(top))
+            .cloned()
+            .collect();
+        for dir in &below {
+            listings.remove(dir);
+        }
+        drop(listings);
+
+        let gone: Vec<WatchDescriptor> = self
+            .watches
+            .iter()
+            .filter(|(_, dir)| dir.starts_with(top))
+            .map(|(wd, _)| wd.clone())
+            .collect();
+        for wd in gone {
+            self.watches.remove(&wd);
+            // Deleted directories already lost their watch
+            let _ = self.inotify.watches().remove(wd);
+        }
+    }
+
+    /// Start over after the kernel dropped events
+    fn rebuild(&mut self) {
+        for root in self.roots.clone() {
+            self.remove_tree(&root);
+            self.add_tree(&root);
+        }
+    }
+
+    /// Apply inotify events until the watcher fails
+    fn run(&mut self) -> Result<(), String> {
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let events = self
+                .inotify
+                .read_events_blocking(&mut buffer)
+                .map_err(|e| format!("Cannot read inotify events: {}", e))?;
+
+            let mut overflowed = false;
+            let mut changes = Vec::new();
+            for event in events {
+                if event.mask.contains(EventMask::Q_OVERFLOW) {
+                    overflowed = true;
+                    continue;
+                }
+                changes.push((event.wd, event.mask, event.name.map(ToOwned::to_owned)));
+            }
+            if overflowed {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: inotify queue overflowed, rebuilding");
+                }
+                self.rebuild();
+                continue;
+            }
+
+            let mut changed = Vec::new();
+            for (wd, mask, name) in changes {
+                // Earlier events may have removed the directory
+                let Some(dir) = self.watches.get(&wd).cloned() else {
+                    continue;
+                };
+                // The watch is gone, most often because the directory was deleted or unmounted
+                if mask.contains(EventMask::IGNORED) {
+                    self.remove_tree(&dir);
+                    continue;
+                }
+                let Some(child) = name.map(|name| dir.join(name)) else {
+                    continue;
+                };
+                if mask.contains(EventMask::ISDIR) {
+                    // Whatever was there before is gone, even if the name came back. Symlinks
+                    // never carry ISDIR, so a directory event is never about a link.
+                    self.remove_tree(&child);
+                    if mask.intersects(EventMask::CREATE | EventMask::MOVED_TO)
+                        && self.keeps(&child)
+                    {
+                        self.add_tree(&child);
+                    }
+                } else {
+                    // Files only matter when they are symlinks, which can point at directories
+                    let listed = self.listings.read().is_ok_and(|listings| {
+                        listings
+                            .get(&dir)
+                            .is_some_and(|c| c.iter().any(|listed| listed.path == child))
+                    });
+                    if !listed && !child.is_symlink() {
+                        continue;
+                    }
+                }
+                if !changed.contains(&dir) {
+                    changed.push(dir);
+                }
+            }
+
+            for dir in changed {
+                let children = walk::read_subdirectories(&dir);
+                if let Ok(mut listings) = self.listings.write() {
+                    if let Some(listing) = listings.get_mut(&dir) {
+                        *listing = children;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Answer listing requests on one connection until the client hangs up
+#[cfg(target_os = "linux")]
+fn serve(stream: UnixStream, listings: &Listings) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+    let mut request = Vec::new();
+    loop {
+        request.clear();
+        if reader.read_until(b'\n', &mut request)? == 0 || request.pop() != Some(b'\n') {
+            return Ok(());
+        }
+        let Ok(listings) = listings.read() else {
+            return Ok(());
+        };
+        for dir in request.split(|&b| b == 0) {
+            let mut answer = vec![b'+'];
+            match listings.get(&path_from_bytes(dir)) {
+                Some(children) => {
+                    for child in children {
+                        answer.push(0);
+                        answer.extend_from_slice(&child.listed_name());
+                    }
+                    // Newlines would end the answer early, so such directories are read by the client
+                    if answer.contains(&b'\n') {
+                        answer = vec![b'?'];
+                    }
+                }
+                None => answer = vec![b'?'],
+            }
+            answer.push(b'\n');
+            writer.write_all(&answer)?;
+        }
+        drop(listings);
+        writer.flush()?;
+    }
+}
+
+/// Watch `roots`, each skipping what its own ignore rules ignore, and answer searches until
+/// killed; progress and warnings are written to `log`
+#[cfg(target_os = "linux")]
+pub fn run(roots: Vec<(PathBuf, IgnoreRules)>, log: Box<dyn Write>) -> Result<(), String> {
+    let socket = socket_path()
+        .ok_or("XDG_RUNTIME_DIR is not set, so there is no place for the daemon's socket")?;
+    if UnixStream::connect(&socket).is_ok() {
+        return Err(format!(
+            "A jcd daemon is already listening on {}",
+            socket.display()
+        ));
+    }
+    // A socket nobody listens on is left over from a daemon that was killed
+    let _ = fs::remove_file(&socket);
+
+    let (roots, ignore_patterns): (Vec<PathBuf>, Vec<IgnoreRules>) = roots
+        .into_iter()
+        .map(|(root, ignore_patterns)| {
+            let ignore_patterns = ignore_patterns.rooted_at(&root);
+            (root, ignore_patterns)
+        })
+        .unzip();
+    let mut watcher = Watcher {
+        inotify: Inotify::init().map_err(|e| format!("Cannot initialize inotify: {}", e))?,
+        roots: roots.clone(),
+        ignore_patterns,
+        listings: Listings::default(),
+        watches: HashMap::new(),
+        log,
+        warned: false,
+    };
+    for root in &roots {
+        watcher.add_tree(root);
+        let watched = watcher
+            .listings
+            .read()
+            .map(|listings| listings.keys().filter(|dir| dir.starts_with(root)).count())
+            .unwrap_or_default();
+        let _ = writeln!(
+            watcher.log,
+            "Watching {} directories below {}",
+            watched,
+            root.display()
+        );
+    }
+
+    let listener = UnixListener::bind(&socket)
+        .map_err(|e| format!("Cannot listen on {}: {}", socket.display(), e))?;
+    let _ = writeln!(watcher.log, "Listening on {}", socket.display());
+    let _ = watcher.log.flush();
+    let listings = Arc::clone(&watcher.listings);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let listings = Arc::clone(&listings);
+            thread::spawn(move || {
+                if let Err(e) = serve(stream, &listings) {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: Dropped a daemon client: {}", e);
+                    }
+                }
+            });
+        }
+    });
+
+    watcher.run()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn run(_roots: Vec<(PathBuf, IgnoreRules)>, _log: Box<dyn Write>) -> Result<(), String> {
+    Err("jcd daemon needs inotify, which is only available on Linux".to_string())
+}
@@ -0,0 +1,41 @@
+// Errors a search reports instead of results. The binary prints them as `Error: <message>`
+// and exits with status 1.
+
+use std::{fmt, path::PathBuf};
+
+/// Why a search could not run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The query had no terms
+    NoSearchTerm,
+    /// A regex or glob term does not compile
+    InvalidPattern { pattern: String, message: String },
+    /// `@name` names no bookmark, and no bookmark starts with it
+    NoBookmark(String),
+    /// `@name` names a bookmark whose directory is gone
+    MissingBookmarkTarget { name: String, path: PathBuf },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoSearchTerm => write!(f, "No search term provided"),
+            Error::InvalidPattern { pattern, message } => {
+                write!(f, "Invalid pattern '{}': {}", pattern, message)
+            }
+            Error::NoBookmark(name) => {
+                write!(f, "No bookmark named '{}' (see `jcd --marks`)", name)
+            }
+            Error::MissingBookmarkTarget { name, path } => write!(
+                f,
+                "Bookmark '{}' points to a missing directory: {}",
+                name,
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
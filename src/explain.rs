@@ -0,0 +1,109 @@
+// `--explain` report. A search run with `SearchOptions::explain` notes every directory it
+// leaves out and why, and the report lists the ranked candidates with the keys that ordered
+// them, followed by those dropped directories.
+
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    ranking::{Ranking, TieBreak},
+    DirectoryMatch, SearchResults,
+};
+
+/// Why a directory is missing from the candidates
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Reason {
+    Ignored(String), // Name matched this ignore pattern
+    TooDeep,         // Deeper than the search goes
+    DepthLimit,      // Sits at the depth limit, so its subdirectories were not searched
+    TimeBudget,      // The search ran out of time here
+    MatchLimit,      // The search had collected enough matches here
+    DirLimit,        // The search had read as many directories as it may here
+    EarlyStop,       // A closer match was found, so the deeper search was skipped
+    Duplicate,       // Found more than once; only the first match is kept
+}
+
+fn describe(reason: &Reason, ignore_file: Option<&Path>) -> String {
+    match reason {
+        Reason::Ignored(pattern) => match ignore_file {
+            Some(file) => format!("ignored by '{}' from {}", pattern, file.display()),
+            None => format!("ignored by '{}'", pattern),
+        },
+        Reason::TooDeep => "past the depth limit".to_string(),
+        Reason::DepthLimit => "not searched below, at the depth limit".to_string(),
+        Reason::TimeBudget => "search stopped here, out of time".to_string(),
+        Reason::MatchLimit => "search stopped here, enough matches found".to_string(),
+        Reason::DirLimit => "search stopped here, directory budget used up".to_string(),
+        Reason::EarlyStop => "searched one level down only, a closer match was found".to_string(),
+        Reason::Duplicate => "duplicate of a listed match".to_string(),
+    }
+}
+
+fn tie_break_key(tie_break: TieBreak, m: &DirectoryMatch, priority: u32) -> String {
+    match tie_break {
+        TieBreak::Frecency => format!("frecency={:.3}", m.frecency),
+        TieBreak::Priority => format!("priority={}", priority),
+        TieBreak::Quality => format!("quality={:?}", m.match_quality),
+        TieBreak::Spread => format!("spread={}", m.spread),
+        TieBreak::Score => format!("score={}", m.score),
+        TieBreak::Depth => format!("depth={}", m.depth_from_current),
+    }
+}
+
+/// Write the report for `query`, whose results were sorted under `ranking`: ranked candidates
+/// first, then dropped directories
+pub fn report(
+    out: &mut impl Write,
+    query: &str,
+    results: &SearchResults,
+    ranking: &Ranking,
+) -> io::Result<()> {
+    let matches = &results.matches;
+    writeln!(out, "Candidates for '{}', best first:", query)?;
+    if matches.is_empty() {
+        writeln!(out, "  (none)")?;
+    }
+    for (i, m) in matches.iter().enumerate() {
+        let priority = ranking.priority(m);
+        let boost = ranking.boost(m.frecency);
+        writeln!(out, "  {}. {}", i, m.path.display())?;
+        write!(
+            out,
+            "     {:?}, depth {}, bucket {}",
+            m.match_quality,
+            m.depth_from_current,
+            priority.saturating_sub(boost)
+        )?;
+        if boost > 0 {
+            write!(out, " (lifted from {} by visit history)", priority)?;
+        }
+        writeln!(out)?;
+        let keys: Vec<String> = ranking
+            .tie_breaks
+            .iter()
+            .map(|&tie_break| tie_break_key(tie_break, m, priority))
+            .collect();
+        writeln!(out, "     tie-breaks: {}", keys.join(" "))?;
+    }
+
+    let ignore_file = results.ignore_file.as_deref();
+    let mut dropped = results.dropped.clone();
+    dropped.sort();
+    dropped.dedup();
+
+    writeln!(out, "Dropped:")?;
+    if dropped.is_empty() {
+        writeln!(out, "  (none)")?;
+    }
+    for (path, reason) in &dropped {
+        writeln!(
+            out,
+            "  {}: {}",
+            path.display(),
+            describe(reason, ignore_file)
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{is_debug_enabled, path_from_bytes};
+
+// Aging keeps the store small: once the summed rank passes the limit every rank is scaled
+// down and entries that fall below the minimum are forgotten
+const MAX_TOTAL_RANK: f64 = 10_000.0;
+const AGING_FACTOR: f64 = 0.9;
+const MIN_RANK: f64 = 1.0;
+
+const HOUR_SECS: u64 = 60 * 60;
+const DAY_SECS: u64 = 24 * HOUR_SECS;
+const WEEK_SECS: u64 = 7 * DAY_SECS;
+
+/// One remembered directory with its visit rank and last visit time (seconds since epoch)
+#[derive(Debug, Clone)]
+pub struct VisitEntry {
+    pub path: PathBuf,
+    pub rank: f64,
+    pub last_visit: u64,
+}
+
+impl VisitEntry {
+    /// Blend visit frequency with recency, weighting recent visits more heavily
+    pub fn frecency(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_visit);
+        if age < HOUR_SECS {
+            self.rank * 4.0
+        } else if age < DAY_SECS {
+            self.rank * 2.0
+        } else if age < WEEK_SECS {
+            self.rank / 2.0
+        } else {
+            self.rank / 4.0
+        }
+    }
+}
+
+/// Persistent visit history stored under `$XDG_DATA_HOME/jcd/visits`
+#[derive(Debug, Default)]
+pub struct VisitStore {
+    file: PathBuf,
+    entries: Vec<VisitEntry>,
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Data directory following the XDG Base Directory Specification
+pub fn data_dir() -> PathBuf {
+    env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            env::var("HOME")
+                .map(|home| PathBuf::from(home).join(".local").join("share"))
+                .unwrap_or_else(|_| PathBuf::from(".local/share"))
+        })
+        .join("jcd")
+}
+
+impl VisitStore {
+    /// Load the visit store from its default location; a missing or unreadable file is empty
+    pub fn load() -> Self {
+        Self::load_from(data_dir().join("visits"))
+    }
+
+    pub fn load_from(file: PathBuf) -> Self {
+        let mut store = VisitStore {
+            file,
+            entries: Vec::new(),
+        };
+
+        let content = match fs::read(&store.file) {
+            Ok(content) => content,
+            Err(_) => {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: No visit store at {}", store.file.display());
+                }
+                return store;
+            }
+        };
+
+        for line in content.split(|&b| b == b'\n') {
+            // Each line is "<rank>\t<last visit>\t<path bytes>"
+            let mut fields = line.splitn(3, |&b| b == b'\t');
+            let (Some(rank), Some(last_visit), Some(path)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let rank = std::str::from_utf8(rank).ok().and_then(|r| r.parse().ok());
+            let last_visit = std::str::from_utf8(last_visit)
+                .ok()
+                .and_then(|t| t.parse().ok());
+            if let (Some(rank), Some(last_visit)) = (rank, last_visit) {
+                store.entries.push(VisitEntry {
+                    path: path_from_bytes(path),
+                    rank,
+                    last_visit,
+                });
+            }
+        }
+
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Loaded {} visit entries from {}",
+                store.entries.len(),
+                store.file.display()
+            );
+        }
+        store
+    }
+
+    pub fn entries(&self) -> &[VisitEntry] {
+        &self.entries
+    }
+
+    /// Frecency score for a directory, zero if it has never been visited
+    pub fn score(&self, dir: &Path, now: u64) -> f64 {
+        self.entries
+            .iter()
+            .find(|entry| entry.path == dir)
+            .map_or(0.0, |entry| entry.frecency(now))
+    }
+
+    /// Count a visit to `dir`, then age the store if it has grown too large. Aging is also when
+    /// directories that no longer exist are pruned, so an ordinary visit stats nothing.
+    pub fn record(&mut self, dir: &Path, now: u64) {
+        match self.entries.iter_mut().find(|entry| entry.path == dir) {
+            Some(entry) => {
+                entry.rank += 1.0;
+                entry.last_visit = now;
+            }
+            None => self.entries.push(VisitEntry {
+                path: dir.to_path_buf(),
+                rank: 1.0,
+                last_visit: now,
+            }),
+        }
+
+        let total: f64 = self.entries.iter().map(|entry| entry.rank).sum();
+        if total > MAX_TOTAL_RANK {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Aging visit store (total rank {:.1})", total);
+            }
+            for entry in &mut self.entries {
+                entry.rank *= AGING_FACTOR;
+            }
+            self.entries
+                .retain(|entry| entry.rank >= MIN_RANK && entry.path.is_dir());
+        }
+    }
+
+    /// Write the store atomically so concurrent shells never see a partial file
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = Vec::new();
+        for entry in &self.entries {
+            let path = crate::path_bytes(&entry.path);
+            // Newlines would corrupt the line-based format, so such paths are not remembered
+            if path.contains(&b'\n') {
+                continue;
+            }
+            write!(content, "{}\t{}\t", entry.rank, entry.last_visit)?;
+            content.extend_from_slice(&path);
+            content.push(b'\n');
+        }
+
+        let tmp = self
+            .file
+            .with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &self.file)
+    }
+}
@@ -0,0 +1,129 @@
+// Scored subsequence matching in the style of fzf: every pattern character must appear in
+// order, and the best alignment is chosen by rewarding word boundaries, camelCase and
+// `-`/`_` transitions and consecutive runs while penalizing gaps.
+
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = -3;
+const SCORE_GAP_EXTENSION: i32 = -1;
+const BONUS_BOUNDARY: i32 = SCORE_MATCH / 2;
+const BONUS_NON_WORD: i32 = SCORE_MATCH / 2;
+const BONUS_CAMEL: i32 = BONUS_BOUNDARY + SCORE_GAP_EXTENSION;
+const BONUS_CONSECUTIVE: i32 = -(SCORE_GAP_START + SCORE_GAP_EXTENSION);
+const BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+// Longer names are not worth aligning; the table is pattern length x name length
+const MAX_NAME_CHARS: usize = 512;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    NonWord,
+    Lower,
+    Upper,
+    Number,
+    Letter,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_lowercase() {
+        CharClass::Lower
+    } else if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_numeric() {
+        CharClass::Number
+    } else if c.is_alphabetic() {
+        CharClass::Letter
+    } else {
+        CharClass::NonWord
+    }
+}
+
+/// Bonus for matching a character of class `current` that follows one of class `previous`
+fn position_bonus(previous: CharClass, current: CharClass) -> i32 {
+    match (previous, current) {
+        (CharClass::NonWord, c) if c != CharClass::NonWord => BONUS_BOUNDARY,
+        (CharClass::Lower, CharClass::Upper) => BONUS_CAMEL,
+        (p, CharClass::Number) if p != CharClass::Number => BONUS_CAMEL,
+        (_, CharClass::NonWord) => BONUS_NON_WORD,
+        _ => 0,
+    }
+}
+
+fn chars_equal(a: char, b: char, case_sensitive: bool) -> bool {
+    a == b || (!case_sensitive && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+/// Best alignment of `pattern` as a subsequence of `name`.
+/// Returns the score and the byte offsets in `name` of each matched character.
+pub fn fuzzy_match(name: &str, pattern: &str, case_sensitive: bool) -> Option<(i32, Vec<usize>)> {
+    let name_chars: Vec<(usize, char)> = name.char_indices().collect();
+    let pattern_chars: Vec<char> = pattern.chars().collect();
+    let (n, m) = (pattern_chars.len(), name_chars.len());
+    if n == 0 || n > m || m > MAX_NAME_CHARS {
+        return None;
+    }
+
+    // The start of the name counts as a word boundary
+    let mut bonus = Vec::with_capacity(m);
+    let mut previous = CharClass::NonWord;
+    for &(_, c) in &name_chars {
+        let class = char_class(c);
+        bonus.push(position_bonus(previous, class));
+        previous = class;
+    }
+
+    // score[i][j]: best score with pattern[i] matched at name[j]; back[i][j]: name index of pattern[i - 1]
+    let mut score = vec![vec![None::<i32>; m]; n];
+    let mut back = vec![vec![0usize; m]; n];
+
+    for (i, &pc) in pattern_chars.iter().enumerate() {
+        // Best predecessor ending at least two characters back, with its gap penalty applied
+        let mut gap: Option<(i32, usize)> = None;
+        for j in i..m {
+            if i > 0 && j >= 2 {
+                let extended = gap.map(|(s, k)| (s + SCORE_GAP_EXTENSION, k));
+                let opened = score[i - 1][j - 2].map(|s| (s + SCORE_GAP_START, j - 2));
+                gap = match (extended, opened) {
+                    (Some(e), Some(o)) => Some(if o.0 >= e.0 { o } else { e }),
+                    (e, o) => e.or(o),
+                };
+            }
+
+            if !chars_equal(name_chars[j].1, pc, case_sensitive) {
+                continue;
+            }
+
+            if i == 0 {
+                score[0][j] = Some(SCORE_MATCH + bonus[j] * BONUS_FIRST_CHAR_MULTIPLIER);
+                continue;
+            }
+
+            let consecutive = if j >= 1 {
+                score[i - 1][j - 1]
+                    .map(|s| (s + SCORE_MATCH + bonus[j].max(BONUS_CONSECUTIVE), j - 1))
+            } else {
+                None
+            };
+            let after_gap = gap.map(|(s, k)| (s + SCORE_MATCH + bonus[j], k));
+            let best = match (consecutive, after_gap) {
+                (Some(c), Some(g)) => Some(if c.0 >= g.0 { c } else { g }),
+                (c, g) => c.or(g),
+            };
+            if let Some((s, k)) = best {
+                score[i][j] = Some(s);
+                back[i][j] = k;
+            }
+        }
+    }
+
+    // Pick the best end position, then walk the back pointers to recover the alignment
+    let (best_score, mut j) = (0..m)
+        .filter_map(|j| score[n - 1][j].map(|s| (s, j)))
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))?;
+
+    let mut positions = vec![0; n];
+    for i in (0..n).rev() {
+        positions[i] = name_chars[j].0;
+        j = back[i][j];
+    }
+    Some((best_score, positions))
+}
@@ -0,0 +1,474 @@
+// Ignore files. Directories matched by the first ignore file found, looking in the directory a
+// search starts from before the config directories, are skipped by every search, unless `-x`
+// bypasses them. Ignore files hold regex patterns matched against a directory's
+// name, or, when named `gitignore` or headed by `#syntax: gitignore`, gitignore rules matched
+// against its path relative to the search root, where the last matching rule wins.
+
+use std::{
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{config_home, is_debug_enabled, MAX_COMPILED_REGEX_SIZE};
+
+const MAX_IGNORE_PATTERNS: usize = 100; // Upper bound on loaded ignore patterns
+
+/// How the lines of an ignore file are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnoreSyntax {
+    Regex,     // Each line is a regex matched against the directory name
+    Gitignore, // Each line is a gitignore rule matched against the path below the search root
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: Regex,
+    source: String, // The line as written, for `--explain`
+    negated: bool,  // `!rule` un-ignores what earlier rules ignored
+}
+
+/// The patterns of one ignore file
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    syntax: IgnoreSyntax,
+    rules: Vec<Rule>,
+    root: Option<PathBuf>, // Gitignore rules match paths relative to this directory
+    file: Option<PathBuf>, // The ignore file the rules were read from, for `--explain`
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        IgnoreRules {
+            syntax: IgnoreSyntax::Regex,
+            rules: Vec::new(),
+            root: None,
+            file: None,
+        }
+    }
+}
+
+impl IgnoreRules {
+    /// Parse an ignore file; a `#syntax: regex` or `#syntax: gitignore` line among the leading
+    /// comments overrides `syntax`
+    pub fn parse(content: &str, syntax: IgnoreSyntax) -> Self {
+        let syntax = header_syntax(content).unwrap_or(syntax);
+        let mut rules = Vec::new();
+
+        for line in content.lines() {
+            let line = match syntax {
+                IgnoreSyntax::Regex => line.trim(),
+                IgnoreSyntax::Gitignore => line.trim_end(),
+            };
+
+            // Skip empty lines and comments
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (source, negated) = match syntax {
+                IgnoreSyntax::Regex => (line.to_string(), false),
+                IgnoreSyntax::Gitignore => match gitignore_regex(line) {
+                    Some(source) => (source, line.starts_with('!')),
+                    None => continue,
+                },
+            };
+
+            // Try to compile the regex pattern
+            match RegexBuilder::new(&source)
+                .size_limit(MAX_COMPILED_REGEX_SIZE)
+                .build()
+            {
+                Ok(regex) => {
+                    if rules.len() < MAX_IGNORE_PATTERNS {
+                        rules.push(Rule {
+                            regex,
+                            source: line.to_string(),
+                            negated,
+                        });
+                    } else if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: Ignored pattern due to max pattern count (100): '{}'",
+                            line
+                        );
+                    }
+                }
+                Err(e) => {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: Invalid regex pattern '{}': {}", line, e);
+                    }
+                    // Continue processing other patterns even if one is invalid
+                }
+            }
+        }
+
+        IgnoreRules {
+            syntax,
+            rules,
+            root: None,
+            file: None,
+        }
+    }
+
+    /// The same rules for a search starting at `root`
+    pub fn rooted_at(&self, root: &Path) -> Self {
+        IgnoreRules {
+            root: Some(root.to_path_buf()),
+            ..self.clone()
+        }
+    }
+
+    /// The ignore file the rules were loaded from, if any
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn syntax(&self) -> IgnoreSyntax {
+        self.syntax
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The line that ignores the directory at `path`, named `dir_name`, if one does
+    pub(crate) fn matching_rule(&self, path: &Path, dir_name: &str) -> Option<&str> {
+        let rule = match self.syntax {
+            IgnoreSyntax::Regex => self.rules.iter().find(|rule| rule.regex.is_match(dir_name)),
+            IgnoreSyntax::Gitignore => {
+                if self.rules.is_empty() {
+                    return None;
+                }
+                let relative = self.relative_path(path);
+                self.rules
+                    .iter()
+                    .rev()
+                    .find(|rule| rule.regex.is_match(&relative))
+                    .filter(|rule| !rule.negated)
+            }
+        };
+        rule.map(|rule| rule.source.as_str())
+    }
+
+    /// `path` below the search root with `/` separators; directories outside it, such as
+    /// parents found up the tree, are matched by their full path
+    fn relative_path(&self, path: &Path) -> String {
+        let relative = self
+            .root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(path);
+        let names: Vec<_> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .collect();
+        names.join("/")
+    }
+}
+
+/// The syntax named by a `#syntax:` line before the first pattern
+fn header_syntax(content: &str) -> Option<IgnoreSyntax> {
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        let comment = line.strip_prefix('#')?;
+        if let Some(value) = comment.trim_start().strip_prefix("syntax:") {
+            return match value.trim() {
+                "regex" => Some(IgnoreSyntax::Regex),
+                "gitignore" => Some(IgnoreSyntax::Gitignore),
+                _ => None,
+            };
+        }
+    }
+    None
+}
+
+/// Translate one gitignore rule into an anchored regex over a `/`-separated relative path.
+/// Rules containing a `/` other than a trailing one are anchored at the search root, others
+/// match at any depth. A trailing `/` limits a rule to directories, which is all jcd matches.
+fn gitignore_regex(line: &str) -> Option<String> {
+    let glob = line.strip_prefix('!').unwrap_or(line);
+    let glob = glob.strip_suffix('/').unwrap_or(glob);
+    if glob.is_empty() || glob == "/" {
+        return None;
+    }
+
+    let anchored = glob.contains('/');
+    let glob = glob.strip_prefix('/').unwrap_or(glob);
+    let mut source = String::from("^");
+    if !anchored {
+        source.push_str("(?:.*/)?");
+    }
+
+    let chars: Vec<char> = glob.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let segment_start = i == 0 || chars[i - 1] == '/';
+        match chars[i] {
+            // `**` as a whole segment spans any number of directories
+            '*' if segment_start
+                && chars.get(i + 1) == Some(&'*')
+                && matches!(chars.get(i + 2), None | Some('/')) =>
+            {
+                if i + 2 == chars.len() {
+                    source.push_str(".*");
+                    i += 2;
+                } else {
+                    source.push_str("(?:.*/)?");
+                    i += 3;
+                }
+                continue;
+            }
+            '*' => source.push_str("[^/]*"),
+            '?' => source.push_str("[^/]"),
+            '[' => {
+                // A bracket expression runs to the next ']', which may itself be the first member
+                let start = i + 1;
+                let mut end = start;
+                if chars.get(end) == Some(&'!') || chars.get(end) == Some(&'^') {
+                    end += 1;
+                }
+                if chars.get(end) == Some(&']') {
+                    end += 1;
+                }
+                while end < chars.len() && chars[end] != ']' {
+                    end += 1;
+                }
+                if end >= chars.len() {
+                    source.push_str(r"\[");
+                } else {
+                    source.push('[');
+                    let mut members = &chars[start..end];
+                    if let Some('!' | '^') = members.first() {
+                        source.push('^');
+                        members = &members[1..];
+                    }
+                    for &c in members {
+                        if matches!(c, '\\' | '[' | ']' | '&' | '~') {
+                            source.push('\\');
+                        }
+                        source.push(c);
+                    }
+                    source.push(']');
+                    i = end;
+                }
+            }
+            // A backslash makes the next character literal, as in `\#` or `\!`
+            '\\' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    source.push_str(&regex::escape(&next.to_string()));
+                    i += 1;
+                }
+            }
+            c => source.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    source.push('$');
+    Some(source)
+}
+
+/// Get ignore file paths in priority order following XDG Base Directory Specification.
+/// Each location may hold a regex file or a gitignore-syntax one; the regex file comes first.
+fn get_ignore_file_paths(dir: &Path) -> Vec<(PathBuf, IgnoreSyntax)> {
+    let mut paths = Vec::new();
+    let mut push = |dir: &Path, regex: &str, gitignore: &str| {
+        paths.push((dir.join(regex), IgnoreSyntax::Regex));
+        paths.push((dir.join(gitignore), IgnoreSyntax::Gitignore));
+    };
+
+    // 1. Project-local ignore file in the directory the search starts from (highest precedence)
+    push(dir, ".jcdignore", ".jcdgitignore");
+
+    // 2. User XDG config directory
+    push(&config_home().join("jcd"), "ignore", "gitignore");
+
+    // 3. Legacy dotfile for backward compatibility
+    if let Ok(home) = env::var("HOME") {
+        push(Path::new(&home), ".jcdignore", ".jcdgitignore");
+    }
+
+    // 4. System-wide configuration
+    push(Path::new("/etc/jcd"), "ignore", "gitignore");
+
+    paths
+}
+
+/// Load ignore patterns from standard locations, with `dir` holding the project-local files
+pub fn load_ignore_patterns(dir: &Path) -> IgnoreRules {
+    let ignore_files = get_ignore_file_paths(dir);
+
+    for (file_path, syntax) in ignore_files {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Checking ignore file: {}", file_path.display());
+        }
+
+        if let Ok(content) = fs::read_to_string(&file_path) {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Found ignore file: {}", file_path.display());
+            }
+            let mut patterns = IgnoreRules::parse(&content, syntax);
+            if is_debug_enabled() {
+                eprintln!(
+                    "DEBUG: Loaded {} ignore patterns ({:?} syntax)",
+                    patterns.len(),
+                    patterns.syntax()
+                );
+            }
+            patterns.file = Some(file_path);
+            return patterns;
+        }
+    }
+
+    if is_debug_enabled() {
+        eprintln!("DEBUG: No ignore file found");
+    }
+    IgnoreRules::default()
+}
+
+/// Check if a directory should be ignored based on patterns; `path` is matched by gitignore
+/// rules
+pub(crate) fn should_ignore_directory(
+    path: &Path,
+    dir_name: &str,
+    ignore_patterns: &IgnoreRules,
+) -> bool {
+    ignore_patterns.matching_rule(path, dir_name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether `rules`, read as a gitignore file, ignore the directory at `path` below the root
+    fn ignores(rules: &str, path: &str) -> bool {
+        let root = Path::new("/search/root");
+        let rules = IgnoreRules::parse(rules, IgnoreSyntax::Gitignore).rooted_at(root);
+        let name = path.rsplit('/').next().unwrap();
+        rules.matching_rule(&root.join(path), name).is_some()
+    }
+
+    #[test]
+    fn plain_names_match_at_any_depth() {
+        assert_eq!(gitignore_regex("build").unwrap(), "^(?:.*/)?build$");
+        assert!(ignores("build", "build"));
+        assert!(ignores("build", "a/b/build"));
+        assert!(!ignores("build", "builder"));
+        assert!(!ignores("build", "build/src"));
+    }
+
+    #[test]
+    fn slashes_anchor_rules_at_the_root() {
+        assert_eq!(gitignore_regex("/build").unwrap(), "^build$");
+        assert!(ignores("/build", "build"));
+        assert!(!ignores("/build", "a/build"));
+        assert!(ignores("web/dist", "web/dist"));
+        assert!(!ignores("web/dist", "app/web/dist"));
+    }
+
+    #[test]
+    fn trailing_slashes_only_limit_rules_to_directories() {
+        assert_eq!(gitignore_regex("dist/"), gitignore_regex("dist"));
+        assert!(ignores("dist/", "app/dist"));
+        assert_eq!(gitignore_regex("/"), None);
+        assert_eq!(gitignore_regex("!"), None);
+    }
+
+    #[test]
+    fn double_stars_span_directories() {
+        assert!(ignores("**/logs", "logs"));
+        assert!(ignores("**/logs", "a/b/logs"));
+        assert!(ignores("a/**/z", "a/z"));
+        assert!(ignores("a/**/z", "a/b/c/z"));
+        assert!(!ignores("a/**/z", "b/z"));
+        assert!(ignores("a/**", "a/b/c"));
+        assert!(!ignores("a/**", "a"));
+        // Not a whole segment, so an ordinary star
+        assert!(ignores("a**b", "axxb"));
+        assert!(!ignores("a**b", "ax/xb"));
+    }
+
+    #[test]
+    fn wildcards_stay_within_one_component() {
+        assert!(ignores("*.egg-info", "pkg/foo.egg-info"));
+        assert!(ignores("cache?", "cache1"));
+        assert!(!ignores("cache?", "cache12"));
+        assert!(!ignores("src/*", "src/a/b"));
+        assert!(ignores("src/*", "src/a"));
+    }
+
+    #[test]
+    fn brackets_match_one_character() {
+        assert!(ignores("[abc]x", "bx"));
+        assert!(!ignores("[abc]x", "dx"));
+        assert!(ignores("[!abc]x", "dx"));
+        assert!(!ignores("[^abc]x", "ax"));
+        assert!(ignores("v[0-9]", "v7"));
+        assert!(ignores("[]]x", "]x"));
+        // An unclosed bracket is literal
+        assert!(ignores("[oops", "[oops"));
+    }
+
+    #[test]
+    fn regex_characters_are_literal() {
+        assert!(ignores("a.b", "a.b"));
+        assert!(!ignores("a.b", "axb"));
+        assert!(ignores("c++", "c++"));
+        assert!(ignores(r"\#notes", "#notes"));
+        assert!(ignores(r"\!important", "!important"));
+    }
+
+    #[test]
+    fn the_last_matching_rule_wins() {
+        let rules = "build*\n!build-keep";
+        assert!(ignores(rules, "build-x"));
+        assert!(!ignores(rules, "build-keep"));
+        assert!(ignores("!build-keep\nbuild*", "build-keep"));
+    }
+
+    #[test]
+    fn regex_rules_match_the_name() {
+        let rules = IgnoreRules::parse("^node_modules$\n# comment\n\n[", IgnoreSyntax::Regex);
+        assert_eq!(rules.len(), 1);
+        let path = Path::new("/a/node_modules");
+        assert_eq!(
+            rules.matching_rule(path, "node_modules"),
+            Some("^node_modules$")
+        );
+        assert_eq!(rules.matching_rule(path, "node_modules2"), None);
+    }
+
+    #[test]
+    fn header_syntax_overrides_the_file_name() {
+        let content = "# build outputs\n#syntax: gitignore\n/build";
+        assert_eq!(header_syntax(content), Some(IgnoreSyntax::Gitignore));
+        let rules = IgnoreRules::parse(content, IgnoreSyntax::Regex);
+        assert_eq!(rules.syntax(), IgnoreSyntax::Gitignore);
+
+        assert_eq!(header_syntax("# syntax: regex"), Some(IgnoreSyntax::Regex));
+        assert_eq!(header_syntax("build\n#syntax: gitignore"), None);
+        assert_eq!(header_syntax("#syntax: glob"), None);
+    }
+
+    #[test]
+    fn parents_outside_the_root_match_by_full_path() {
+        let root = Path::new("/search/root");
+        let rules = IgnoreRules::parse("/search", IgnoreSyntax::Gitignore).rooted_at(root);
+        assert!(rules
+            .matching_rule(Path::new("/search"), "search")
+            .is_some());
+        assert!(rules
+            .matching_rule(&root.join("search"), "search")
+            .is_some());
+    }
+}
@@ -0,0 +1,452 @@
+// Persistent directory index. `jcd index build` records the subdirectories of every
+// directory below a root, with the directory's modification time, under
+// `$XDG_CACHE_HOME/jcd/index/`. Name searches that start inside an indexed root match the
+// indexed directories directly instead of walking, and other searches list directories from
+// the index instead of reading them. Either way a directory whose mtime is unchanged still has
+// the same entries, so only the directories that changed since the last run are read again,
+// and the refreshed listings are written back after the search.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::UNIX_EPOCH,
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    frecency::now_secs,
+    ignore::{should_ignore_directory, IgnoreRules},
+    is_debug_enabled, path_bytes, path_from_bytes,
+    walk::{self, Subdirectory},
+};
+
+// Bumped when the format changes; older files are not loaded and need `jcd index build`
+const HEADER: &[u8] = b"jcd-index2";
+
+/// Modification time as seconds and nanoseconds since the epoch
+type Mtime = (u64, u32);
+
+/// A directory's subdirectories as of its modification time
+#[derive(Debug, Clone)]
+struct Listing {
+    mtime: Mtime,
+    children: Vec<Subdirectory>, // Sorted by name, as `walk::read_subdirectories` lists them
+}
+
+/// The index of one root, stored under `$XDG_CACHE_HOME/jcd/index/<hash of the root>`
+#[derive(Debug)]
+pub struct DirIndex {
+    file: PathBuf,
+    root: PathBuf,
+    built: u64, // Seconds since the epoch
+    listings: BTreeMap<PathBuf, Listing>,
+    changed: bool,
+}
+
+/// Cache directory following the XDG Base Directory Specification
+fn cache_dir() -> PathBuf {
+    env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            env::var("HOME")
+                .map(|home| PathBuf::from(home).join(".cache"))
+                .unwrap_or_else(|_| PathBuf::from(".cache"))
+        })
+        .join("jcd")
+        .join("index")
+}
+
+/// Index file for `root`, named by a stable FNV-1a hash of its path
+fn index_file(root: &Path) -> PathBuf {
+    let hash = path_bytes(root)
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    cache_dir().join(format!("{:016x}", hash))
+}
+
+fn mtime(dir: &Path) -> Option<Mtime> {
+    let modified = fs::metadata(dir).ok()?.modified().ok()?;
+    let since_epoch = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Some((since_epoch.as_secs(), since_epoch.subsec_nanos()))
+}
+
+impl DirIndex {
+    pub fn load_from(file: PathBuf) -> Option<Self> {
+        let content = fs::read(&file).ok()?;
+        let mut lines = content.split(|&b| b == b'\n');
+
+        // The header is "jcd-index\t<built>\t<root bytes>"
+        let mut header = lines.next()?.splitn(3, |&b| b == b'\t');
+        if header.next()? != HEADER {
+            return None;
+        }
+        let built = std::str::from_utf8(header.next()?).ok()?.parse().ok()?;
+        let root = path_from_bytes(header.next()?);
+
+        let mut listings = BTreeMap::new();
+        for line in lines {
+            // Each line is "<secs>.<nanos>\t<dir bytes>", then "\0<name bytes>" per subdirectory,
+            // with a trailing "/" for symlinks
+            let mut fields = line.splitn(2, |&b| b == b'\t');
+            let (Some(mtime), Some(rest)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let Some(mtime) = std::str::from_utf8(mtime).ok().and_then(|mtime| {
+                let (secs, nanos) = mtime.split_once('.')?;
+                Some((secs.parse().ok()?, nanos.parse().ok()?))
+            }) else {
+                continue;
+            };
+            let mut fields = rest.split(|&b| b == 0);
+            let dir = path_from_bytes(fields.next().unwrap_or_default());
+            let children = fields
+                .map(|name| Subdirectory::from_listed_name(&dir, name))
+                .collect();
+            listings.insert(dir, Listing { mtime, children });
+        }
+
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Loaded index of {} with {} directories",
+                root.display(),
+                listings.len()
+            );
+        }
+        Some(DirIndex {
+            file,
+            root,
+            built,
+            listings,
+            changed: false,
+        })
+    }
+
+    /// Walk everything below `root` except ignored directories and symlinks, and index it;
+    /// gitignore rules are anchored at `root`
+    pub fn build(root: &Path, ignore_patterns: &IgnoreRules) -> Self {
+        DirIndex {
+            file: index_file(root),
+            root: root.to_path_buf(),
+            built: now_secs(),
+            listings: read_tree(root, &ignore_patterns.rooted_at(root)),
+            changed: true,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn built(&self) -> u64 {
+        self.built
+    }
+
+    pub fn len(&self) -> usize {
+        self.listings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listings.is_empty()
+    }
+
+    /// Indexed directories whose listing is out of date
+    pub fn stale(&self) -> usize {
+        self.listings
+            .par_iter()
+            .filter(|(dir, listing)| mtime(dir) != Some(listing.mtime))
+            .count()
+    }
+
+    /// Record a fresh listing of `dir`, forgetting the subtrees of subdirectories that are gone
+    fn update(&mut self, dir: &Path, mtime: Mtime, children: Vec<Subdirectory>) {
+        if let Some(old) = self.listings.get(dir) {
+            let gone: Vec<PathBuf> = old
+                .children
+                .iter()
+                .filter(|child| !children.contains(child))
+                .map(|child| child.path.clone())
+                .collect();
+            // A subtree sorts right after its root, so it is one contiguous range
+            for child in gone {
+                let below: Vec<PathBuf> = self
+                    .listings
+                    .range(child.clone()..)
+                    .map(|(indexed, _)| indexed)
+                    .take_while(|indexed| indexed.starts_with(&child))
+                    .cloned()
+                    .collect();
+                for indexed in below {
+                    self.listings.remove(&indexed);
+                }
+            }
+        }
+        self.listings
+            .insert(dir.to_path_buf(), Listing { mtime, children });
+        self.changed = true;
+    }
+
+    /// Write the index atomically so concurrent shells never see a partial file
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = Vec::new();
+        content.extend_from_slice(HEADER);
+        write!(content, "\t{}\t", self.built)?;
+        content.extend_from_slice(&path_bytes(&self.root));
+        content.push(b'\n');
+        for (dir, listing) in &self.listings {
+            let mut line = Vec::new();
+            write!(line, "{}.{}\t", listing.mtime.0, listing.mtime.1)?;
+            line.extend_from_slice(&path_bytes(dir));
+            for child in &listing.children {
+                line.push(0);
+                line.extend_from_slice(&child.listed_name());
+            }
+            // Newlines would corrupt the line-based format, so such directories are read each time
+            if line.contains(&b'\n') {
+                continue;
+            }
+            content.extend_from_slice(&line);
+            content.push(b'\n');
+        }
+
+        let tmp = self
+            .file
+            .with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &self.file)
+    }
+}
+
+/// Listings of `top` and everything below it except ignored directories and symlinks, read
+/// from disk one level at a time
+fn read_tree(top: &Path, ignore_patterns: &IgnoreRules) -> BTreeMap<PathBuf, Listing> {
+    let mut listings = BTreeMap::new();
+    let mut level = vec![top.to_path_buf()];
+    while !level.is_empty() {
+        let read: Vec<(PathBuf, Option<Mtime>, Vec<Subdirectory>)> = level
+            .into_par_iter()
+            .map(|dir| {
+                let mtime = mtime(&dir);
+                let children = walk::read_subdirectories(&dir);
+                (dir, mtime, children)
+            })
+            .collect();
+
+        level = Vec::new();
+        for (dir, mtime, children) in read {
+            let Some(mtime) = mtime else {
+                continue;
+            };
+            level.extend(
+                children
+                    .iter()
+                    .filter(|child| {
+                        let name = child.path.file_name().unwrap_or_default().to_string_lossy();
+                        !child.link && !should_ignore_directory(&child.path, &name, ignore_patterns)
+                    })
+                    .map(|child| child.path.clone()),
+            );
+            listings.insert(dir, Listing { mtime, children });
+        }
+    }
+    listings
+}
+
+/// Every index on disk, in no particular order
+pub fn all() -> Vec<DirIndex> {
+    let Ok(entries) = fs::read_dir(cache_dir()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().contains('.'))
+        .filter_map(|entry| DirIndex::load_from(entry.path()))
+        .collect()
+}
+
+/// Delete the index of `root`; returns false if there was none
+pub fn remove(root: &Path) -> io::Result<bool> {
+    match fs::remove_file(index_file(root)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// The indexes one `Searcher` has looked up; each file is read at most once and shared by
+/// every search that starts inside its root
+#[derive(Debug, Default)]
+pub struct Indexes(Mutex<HashMap<PathBuf, Option<Arc<OpenIndex>>>>);
+
+impl Indexes {
+    /// The index of `dir` or of its nearest indexed ancestor
+    pub fn covering(&self, dir: &Path) -> Option<Arc<OpenIndex>> {
+        let mut loaded = self.0.lock().ok()?;
+        dir.ancestors().find_map(|root| {
+            loaded
+                .entry(root.to_path_buf())
+                .or_insert_with(|| {
+                    DirIndex::load_from(index_file(root))
+                        .map(|index| Arc::new(OpenIndex::new(index)))
+                })
+                .clone()
+        })
+    }
+}
+
+/// The directories an index lists below one of its directories
+#[derive(Debug, Default)]
+pub struct Subtree {
+    pub dirs: Vec<Subdirectory>, // Every listed subdirectory, in path order
+    pub unread: Vec<PathBuf>,    // Those never read, because they were ignored when indexed
+}
+
+/// An index in use by searches, refreshed as they read and written back after each one
+#[derive(Debug)]
+pub struct OpenIndex(Mutex<DirIndex>);
+
+impl OpenIndex {
+    pub fn new(index: DirIndex) -> Self {
+        OpenIndex(Mutex::new(index))
+    }
+
+    /// Everything the index lists below `dir`, without checking it against the disk; `None`
+    /// when `dir` itself is not indexed
+    pub fn below(&self, dir: &Path) -> Option<Subtree> {
+        let index = self.0.lock().ok()?;
+        index.listings.get(dir)?;
+        let mut subtree = Subtree::default();
+        // A subtree sorts right after its root, so it is one contiguous range
+        for (_, listing) in index
+            .listings
+            .range(dir.to_path_buf()..)
+            .take_while(|(indexed, _)| indexed.starts_with(dir))
+        {
+            for child in &listing.children {
+                if !child.link && !index.listings.contains_key(&child.path) {
+                    subtree.unread.push(child.path.clone());
+                }
+                subtree.dirs.push(child.clone());
+            }
+        }
+        subtree.dirs.sort_by(|a, b| a.path.cmp(&b.path));
+        Some(subtree)
+    }
+
+    /// Subdirectories of `dir` from the index, refreshed first if `dir` changed since it was
+    /// indexed. `None` when `dir` is outside the indexed root.
+    pub fn subdirectories(&self, dir: &Path) -> Option<Vec<Subdirectory>> {
+        self.refresh(dir).map(|(children, _)| children)
+    }
+
+    /// Like `subdirectories`, but also returns the subdirectories the index did not list
+    /// before. `None` as well when `dir` is gone.
+    pub fn refresh(&self, dir: &Path) -> Option<(Vec<Subdirectory>, Vec<Subdirectory>)> {
+        let indexed = {
+            let index = self.0.lock().ok()?;
+            if !dir.starts_with(&index.root) {
+                return None;
+            }
+            index.listings.get(dir).cloned()
+        };
+
+        let mtime = mtime(dir)?;
+        if let Some(listing) = &indexed {
+            if listing.mtime == mtime {
+                return Some((listing.children.clone(), Vec::new()));
+            }
+        }
+
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Refreshing index entry for {}", dir.display());
+        }
+        let children = walk::read_subdirectories(dir);
+        let gained = children
+            .iter()
+            .filter(|child| {
+                indexed
+                    .as_ref()
+                    .is_none_or(|old| !old.children.contains(child))
+            })
+            .cloned()
+            .collect();
+        if let Ok(mut index) = self.0.lock() {
+            index.update(dir, mtime, children.clone());
+        }
+        Some((children, gained))
+    }
+
+    /// Read `top` and everything below it that `ignore_patterns` keeps into the index
+    pub fn add_tree(&self, top: &Path, ignore_patterns: &IgnoreRules) {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Adding {} to the index", top.display());
+        }
+        let listings = read_tree(top, ignore_patterns);
+        if let Ok(mut index) = self.0.lock() {
+            index.listings.extend(listings);
+            index.changed = true;
+        }
+    }
+
+    /// Write back the index if searches refreshed any of it since it was last saved
+    pub fn save(&self) {
+        let Ok(mut index) = self.0.lock() else {
+            return;
+        };
+        if !index.changed {
+            return;
+        }
+        match index.save() {
+            Ok(()) => index.changed = false,
+            Err(e) => {
+                if is_debug_enabled() {
+                    eprintln!(
+                        "DEBUG: Cannot save index of {}: {}",
+                        index.root.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn indexes_are_read_once() {
+        let scratch = env::temp_dir().join(format!("jcd_unit_indexes_{}", process::id()));
+        let _ = fs::remove_dir_all(&scratch);
+        let root = scratch.join("root");
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        env::set_var("XDG_CACHE_HOME", scratch.join("cache"));
+        DirIndex::build(&root, &IgnoreRules::default())
+            .save()
+            .unwrap();
+
+        let indexes = Indexes::default();
+        let first = indexes.covering(&root.join("sub")).unwrap();
+        // Later lookups below the same root reuse what was loaded, even with the file gone
+        fs::remove_file(index_file(&root)).unwrap();
+        let again = indexes.covering(&root.join("sub/deeper")).unwrap();
+        let at_root = indexes.covering(&root).unwrap();
+        fs::remove_dir_all(&scratch).unwrap();
+
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(Arc::ptr_eq(&first, &at_root));
+        assert!(Indexes::default().covering(&root).is_none());
+    }
+}
@@ -0,0 +1,122 @@
+//! jcd's search engine. A [`Searcher`] built from [`SearchOptions`] finds the directories
+//! matching a query around a starting directory and ranks them the way the `jcd` command
+//! does, so launchers and other tools can embed the ranking instead of running the binary:
+//!
+//! ```no_run
+//! use jcd::{SearchOptions, Searcher};
+//!
+//! let searcher = Searcher::new(SearchOptions::default());
+//! let results = searcher.search(&["api"], std::path::Path::new("/home/user/src"))?;
+//! for m in &results.matches {
+//!     println!("{:?} {}", m.match_quality, m.path.display());
+//! }
+//! # Ok::<(), jcd::Error>(())
+//! ```
+//!
+//! Every search strategy compares names through a [`Matcher`] and sorts through a [`Ranker`],
+//! so either can be replaced without touching the walks:
+//!
+//! ```no_run
+//! use std::{cmp::Ordering, sync::Arc};
+//! use jcd::{DirectoryMatch, Ranker, SearchOptions, Searcher};
+//!
+//! // Shortest path first, whatever the match
+//! #[derive(Debug)]
+//! struct Shortest;
+//!
+//! impl Ranker for Shortest {
+//!     fn compare(&self, a: &DirectoryMatch, b: &DirectoryMatch) -> Ordering {
+//!         a.path.as_os_str().len().cmp(&b.path.as_os_str().len())
+//!     }
+//! }
+//!
+//! let options = SearchOptions {
+//!     ranker: Some(Arc::new(Shortest)),
+//!     ..SearchOptions::default()
+//! };
+//! let results = Searcher::new(options).search(&["api"], std::path::Path::new("."))?;
+//! # Ok::<(), jcd::Error>(())
+//! ```
+//!
+//! The public modules hold the pieces the `jcd` binary is built from: the config file,
+//! bookmarks, visit history, the directory index and daemon, and the `--explain` report.
+//! Each search loads its own ignore file, opens its own index and daemon connection, and
+//! keeps its own `--explain` notes, so one `Searcher` can serve several threads at once.
+
+pub mod bookmarks;
+pub mod budget;
+pub mod config;
+pub mod daemon;
+pub mod explain;
+pub mod frecency;
+pub mod ignore;
+pub mod index;
+pub mod normalize;
+pub mod pattern;
+pub mod ranking;
+pub mod walk;
+
+mod acronym;
+mod error;
+mod fuzzy;
+mod matching;
+mod search;
+mod searcher;
+mod typo;
+
+use std::{
+    borrow::Cow,
+    env,
+    path::{Path, PathBuf},
+};
+
+pub use budget::{Limit, SearchBudget};
+pub use error::{Error, Result};
+pub use matching::{CasePolicy, MatchOptions, Matcher, NameMatch, NameMatchKind};
+pub use normalize::Normalization;
+pub use pattern::{CompiledPatterns, PatternMode};
+pub use ranking::{Ranker, Ranking};
+pub use search::{DirectoryMatch, MatchQuality};
+pub use searcher::{SearchOptions, SearchResults, Searcher};
+
+const MAX_COMPILED_REGEX_SIZE: usize = 1_000_000; // 1MB compiled regex size limit
+
+/// Config directory following the XDG Base Directory Specification
+pub fn config_home() -> PathBuf {
+    env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            env::var("HOME")
+                .map(|home| PathBuf::from(home).join(".config"))
+                .unwrap_or_else(|_| PathBuf::from(".config"))
+        })
+}
+
+/// Whether `JCD_DEBUG=1` asks for a trace of the search on stderr
+pub fn is_debug_enabled() -> bool {
+    env::var("JCD_DEBUG").unwrap_or_default() == "1"
+}
+
+/// Raw bytes of a path exactly as the kernel reported them
+#[cfg(unix)]
+pub fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+pub fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    Cow::Owned(path.to_string_lossy().into_owned().into_bytes())
+}
+
+/// Rebuild a path from bytes written by `path_bytes`
+#[cfg(unix)]
+pub fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+pub fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
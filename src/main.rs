@@ -1,372 +1,641 @@
-use regex::{Regex, RegexBuilder};
+// The jcd command: parses flags, runs a `jcd::Searcher` and prints the ranked matches for the
+// shell wrappers, plus the bookmark, index, daemon and `init` commands.
+
+use jcd::{
+    bookmarks, bookmarks::BookmarkStore, budget, config::Config, daemon, explain, frecency,
+    frecency::VisitStore, ignore::load_ignore_patterns, index, is_debug_enabled, path_bytes, walk,
+    CasePolicy, DirectoryMatch, MatchQuality, Normalization, PatternMode, SearchOptions, Searcher,
+};
 use std::{
+    borrow::Cow,
     env, fs,
     io::{self, Write},
     path::{Path, PathBuf},
     process,
     sync::{Arc, Mutex},
     thread,
-    time::{Duration, Instant},
+    time::Duration,
 };
 
-// Configuration constants for performance tuning
-const MAX_MATCHES: usize = 20; // Stop after finding enough matches
-const MAX_SEARCH_TIME_MS: u64 = 500; // Max time to spend searching (milliseconds)
-const MAX_IGNORE_PATTERNS: usize = 100; // Upper bound on loaded ignore patterns
-const MAX_COMPILED_REGEX_SIZE: usize = 1_000_000; // 1MB compiled regex size limit
-
-/// Get ignore file paths in priority order following XDG Base Directory Specification
-fn get_ignore_file_paths() -> Vec<PathBuf> {
-    let mut paths = Vec::new();
-
-    // 1. Project-local ignore file (highest precedence)
-    if let Ok(current_dir) = env::current_dir() {
-        paths.push(current_dir.join(".jcdignore"));
-    }
-
-    // 2. User XDG config directory
-    let config_home = env::var("XDG_CONFIG_HOME")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| {
-            env::var("HOME")
-                .map(|home| PathBuf::from(home).join(".config"))
-                .unwrap_or_else(|_| PathBuf::from(".config"))
-        });
-    paths.push(config_home.join("jcd").join("ignore"));
-
-    // 3. Legacy dotfile for backward compatibility
-    if let Ok(home) = env::var("HOME") {
-        paths.push(PathBuf::from(home).join(".jcdignore"));
-    }
-
-    // 4. System-wide configuration
-    paths.push(PathBuf::from("/etc/jcd/ignore"));
-
-    paths
+// Shell integration shipped inside the binary so `jcd init` always matches this version
+const SHELL_FUNCTION_SCRIPT: &str = include_str!("../jcd_function.sh");
+const SHELL_BINARY_DEFAULT: &str = "\"${JCD_BINARY:-/usr/bin/jcd}\"";
+
+// Search flags forwarded by the generated shell integrations as (short, long, description);
+// either spelling may be empty
+const PASSTHROUGH_FLAGS: &[(&str, &str, &str)] = &[
+    ("-i", "", "Case-insensitive matching"),
+    ("-s", "--smart-case", "Smart case matching (the default)"),
+    ("-S", "--case-sensitive", "Case-sensitive matching"),
+    (
+        "-u",
+        "--unicode",
+        "Unicode normalization and full case folding",
+    ),
+    ("-a", "--ignore-accents", "Ignore accents (implies -u)"),
+    ("", "--no-unicode", "Turn Unicode normalization off again"),
+    ("-x", "", "Bypass ignore patterns"),
+    ("-f", "--fuzzy", "Fuzzy subsequence matching"),
+    ("", "--no-fuzzy", "Turn fuzzy matching off again"),
+    (
+        "-w",
+        "--acronym",
+        "Match word initials (fbs finds foo-bar-service)",
+    ),
+    ("", "--no-acronym", "Turn acronym matching off again"),
+    ("-r", "--regex", "Treat the pattern as a regular expression"),
+    ("-g", "--glob", "Treat the pattern as a shell glob"),
+    (
+        "-F",
+        "--fixed",
+        "Match the pattern literally, even with * ? or [",
+    ),
+];
+
+// The bash/zsh flag list line, replaced from PASSTHROUGH_FLAGS by `jcd init`
+const SHELL_FLAGS_LINE: &str = "_JCD_SEARCH_FLAGS=(";
+
+// Fish integration template; @BINARY@, @FLAGS@ and @FLAG_COMPLETIONS@ are filled in by `jcd init fish`
+const FISH_SCRIPT_TEMPLATE: &str = r#"function __jcd_usage
+    echo "Usage:"
+    echo "  jcd [flags] <directory_pattern>   - Changes directory according to the pattern"
+    echo "  jcd @<name>[/pattern]             - Changes to a bookmark, or searches below it"
+    echo "  jcd <term> <term>...              - Every term in path order, the last one in the directory name"
+    echo "  jcd --mark <name> | --marks | --unmark <name>"
+    echo "  jcd --explain [flags] <directory_pattern>"
+    echo "  jcd index build [dir] | status | clear [dir]"
+    echo "  jcd daemon watch [dir...]"
+end
+
+function __jcd_flags --description 'Collect jcd search flags from a token list'
+    for token in $argv
+        if contains -- $token @FLAGS@
+            printf '%s\n' $token
+        end
+    end
+end
+
+function __jcd_record_visit --description 'Count a visit in the jcd frecency store'
+    if test "$JCD_RECORD" != 0
+        @BINARY@ --record $PWD >/dev/null 2>&1
+    end
+end
+
+function jcd --description 'Jump to a directory matching a pattern'
+    # Bookmark commands, --explain, index and daemon commands go straight to the binary
+    if contains -- "$argv[1]" --mark --marks --unmark --explain
+        @BINARY@ $argv
+        return
+    end
+    if test "$argv[1]" = index; and contains -- "$argv[2]" build status clear
+        @BINARY@ $argv
+        return
+    end
+    if test "$argv[1]" = daemon; and test "$argv[2]" = watch
+        @BINARY@ $argv
+        return
+    end
+
+    set -l flags (__jcd_flags $argv)
+    set -l terms
+    for arg in $argv
+        if not contains -- $arg $flags
+            set -a terms $arg
+        end
+    end
+
+    if test -z "$terms[1]"
+        __jcd_usage
+        return 1
+    end
+    set -l pattern "$terms"
+
+    # A trailing slash on an existing directory navigates there directly
+    if test (count $terms) -eq 1; and string match -q -- '*/' $pattern
+        set -l dir (string replace -r -- '/+$' '' $pattern)
+        if test -n "$dir"; and test -d "$dir"
+            cd $dir; or return
+            __jcd_record_visit
+            return
+        end
+    end
+
+    set -l dest (@BINARY@ $flags $terms --index 0 -0 | string split0)
+    if test -z "$dest[1]"
+        echo "No directories found matching '$pattern'"
+        return 1
+    end
+    cd $dest[1]; or return
+    __jcd_record_visit
+end
+
+function __jcd_complete --description 'Ranked jcd matches for the token under the cursor'
+    set -l cur (commandline -ct)
+    if test -z "$cur"
+        return
+    end
+    set -l flags (__jcd_flags (commandline -opc))
+    @BINARY@ $flags $cur --list -0 --quiet 2>/dev/null | string split0
+end
+
+complete -c jcd -f
+complete -c jcd -l mark -x -d 'Bookmark the current directory'
+complete -c jcd -l marks -d 'List bookmarks'
+complete -c jcd -l unmark -x -d 'Delete a bookmark'
+complete -c jcd -l explain -d 'Show how every candidate was ranked or dropped'
+complete -c jcd -l no-index -d 'Search without the directory index or daemon'
+@FLAG_COMPLETIONS@
+complete -c jcd -a '(__jcd_complete)'
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Plain, // One path per line
+    Json,  // One JSON object per match (JSON Lines)
+    Nul,   // Raw path bytes, each terminated by NUL (-0)
+    Shell, // One shell-quoted word per line, safe to eval in bash and zsh
 }
 
-/// Parse ignore patterns from file content
-fn parse_ignore_patterns(content: &str) -> Vec<Regex> {
-    let mut patterns = Vec::new();
-
-    for line in content.lines() {
-        let line = line.trim();
+/// Add a visit to the persistent frecency store
+fn record_visit(dir: &Path) -> io::Result<()> {
+    let dir = dir.canonicalize()?;
+    let mut store = VisitStore::load();
+    store.record(&dir, frecency::now_secs());
+    store.save()
+}
 
-        // Skip empty lines and comments
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
+/// Save the current directory under `name`, replacing any existing bookmark with that name
+fn mark_current_dir(name: &str) -> io::Result<()> {
+    if !bookmarks::is_valid_name(name) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "names may only contain letters, digits, '-', '_' and '.'",
+        ));
+    }
+    let dir = env::current_dir()?.canonicalize()?;
+    let mut store = BookmarkStore::load();
+    store.set(name, &dir);
+    store.save()
+}
 
-        // Try to compile the regex pattern
-        match RegexBuilder::new(line)
-            .size_limit(MAX_COMPILED_REGEX_SIZE)
-            .build()
-        {
-            Ok(regex) => {
-                if patterns.len() < MAX_IGNORE_PATTERNS {
-                    patterns.push(regex);
-                } else if is_debug_enabled() {
-                    eprintln!(
-                        "DEBUG: Ignored pattern due to max pattern count (100): '{}'",
-                        line
-                    );
-                }
-            }
-            Err(e) => {
-                if is_debug_enabled() {
-                    eprintln!("DEBUG: Invalid regex pattern '{}': {}", line, e);
-                }
-                // Continue processing other patterns even if one is invalid
-            }
+/// Print every bookmark as an aligned name/path table
+fn list_bookmarks() {
+    let store = BookmarkStore::load();
+    let width = store
+        .marks()
+        .iter()
+        .map(|(name, _)| name.len() + 1)
+        .max()
+        .unwrap_or(0);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for (name, path) in store.marks() {
+        let written = write!(out, "{:<width$}  ", format!("@{}", name), width = width)
+            .and_then(|_| out.write_all(&path_bytes(path)))
+            .and_then(|_| out.write_all(b"\n"));
+        if written.is_err() {
+            break;
         }
     }
-
-    patterns
 }
 
-/// Load ignore patterns from standard locations
-fn load_ignore_patterns() -> Vec<Regex> {
-    let ignore_files = get_ignore_file_paths();
+/// `jcd index build [dir]`, `jcd index status` and `jcd index clear [dir]`
+fn index_command(command: &str, dir: Option<&str>) -> Result<(), String> {
+    let root = match dir {
+        Some(dir) => fs::canonicalize(dir).map_err(|e| format!("Cannot index {}: {}", dir, e))?,
+        None => env::current_dir().map_err(|e| format!("Cannot get current directory: {}", e))?,
+    };
 
-    for file_path in ignore_files {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Checking ignore file: {}", file_path.display());
+    match command {
+        "build" => {
+            let index = index::DirIndex::build(&root, &load_ignore_patterns(&root));
+            index
+                .save()
+                .map_err(|e| format!("Cannot save index of {}: {}", root.display(), e))?;
+            println!(
+                "Indexed {} directories below {}",
+                index.len(),
+                root.display()
+            );
         }
-
-        if let Ok(content) = fs::read_to_string(&file_path) {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Found ignore file: {}", file_path.display());
+        "status" => {
+            let mut indexes = index::all();
+            if indexes.is_empty() {
+                println!("No directory indexes");
+            }
+            indexes.sort_by(|a, b| a.root().cmp(b.root()));
+            let now = frecency::now_secs();
+            for index in indexes {
+                println!(
+                    "{}: {} directories, built {} ago, {} changed since",
+                    index.root().display(),
+                    index.len(),
+                    format_age(now.saturating_sub(index.built())),
+                    index.stale()
+                );
             }
-            let patterns = parse_ignore_patterns(&content);
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Loaded {} ignore patterns", patterns.len());
+        }
+        "clear" if dir.is_none() => {
+            let indexes = index::all();
+            for index in &indexes {
+                index::remove(index.root()).map_err(|e| format!("Cannot remove index: {}", e))?;
+            }
+            match indexes.len() {
+                1 => println!("Removed 1 directory index"),
+                count => println!("Removed {} directory indexes", count),
             }
-            return patterns;
         }
+        "clear" => {
+            let removed =
+                index::remove(&root).map_err(|e| format!("Cannot remove index: {}", e))?;
+            if !removed {
+                return Err(format!("No index of {}", root.display()));
+            }
+            println!("Removed the index of {}", root.display());
+        }
+        _ => return Err(format!("Unknown index command '{}'", command)),
     }
-
-    if is_debug_enabled() {
-        eprintln!("DEBUG: No ignore file found");
-    }
-    Vec::new()
+    Ok(())
 }
 
-/// Check if a directory should be ignored based on patterns
-fn should_ignore_directory(dir_name: &str, ignore_patterns: &[Regex]) -> bool {
-    ignore_patterns
+/// `jcd daemon watch [dir...]`; without directories it watches the `daemon.root`s of the config
+fn daemon_command(dirs: &[String]) -> Result<(), String> {
+    let roots = if dirs.is_empty() {
+        Config::load().daemon_roots
+    } else {
+        dirs.iter().map(PathBuf::from).collect()
+    };
+    if roots.is_empty() {
+        return Err(
+            "Nothing to watch: name directories or set daemon.root in the config".to_string(),
+        );
+    }
+    let roots = roots
         .iter()
-        .any(|pattern| pattern.is_match(dir_name))
+        .map(|root| {
+            let root = fs::canonicalize(root)
+                .map_err(|e| format!("Cannot watch {}: {}", root.display(), e))?;
+            let ignore_patterns = load_ignore_patterns(&root);
+            Ok((root, ignore_patterns))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    daemon::run(roots, Box::new(io::stdout()))
 }
 
-fn is_debug_enabled() -> bool {
-    env::var("JCD_DEBUG").unwrap_or_default() == "1"
+/// Coarse age for `jcd index status`, such as `5m` or `3d`
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-enum MatchQuality {
-    ExactUp,     // Exact match up the path - highest priority
-    PartialUp,   // Partial match up the path - second priority
-    ExactDown,   // Exact match down the path - third priority
-    PrefixDown,  // Prefix match down the path - fourth priority
-    PartialDown, // Partial match down the path - lowest priority
-}
+/// Build the shell integration script for `jcd init <shell>`, pointing it at this binary
+fn shell_init_script(shell: &str) -> Result<String, String> {
+    let exe = env::current_exe()
+        .and_then(|exe| exe.canonicalize())
+        .map_err(|e| format!("Cannot determine jcd binary path: {}", e))?;
+    let binary = shell_quote(&path_bytes(&exe));
+
+    let script = match shell {
+        "bash" | "zsh" => shell_function_script(&binary),
+        "fish" => fish_init_script(&fish_quote(&path_bytes(&exe))),
+        _ => {
+            return Err(format!(
+                "Unsupported shell '{}' (supported shells: bash, zsh, fish)",
+                shell
+            ))
+        }
+    };
 
-#[derive(Debug, Clone)]
-struct DirectoryMatch {
-    path: PathBuf,
-    depth_from_current: i32, // negative for parents, positive for children
-    match_quality: MatchQuality,
+    Ok(format!(
+        "# Generated by `jcd init {}` - do not edit, regenerate after upgrading jcd\n{}",
+        shell, script
+    ))
 }
 
-#[derive(Debug)]
-struct SearchContext {
-    start_time: Instant,
-    max_matches: usize,
-    max_time: Duration,
-    current_matches: usize,
+/// Every spelling of the pass-through flags, short before long
+fn passthrough_flags() -> Vec<&'static str> {
+    PASSTHROUGH_FLAGS
+        .iter()
+        .flat_map(|(short, long, _)| [*short, *long])
+        .filter(|flag| !flag.is_empty())
+        .collect()
 }
 
-impl SearchContext {
-    fn new() -> Self {
-        Self {
-            start_time: Instant::now(),
-            max_matches: MAX_MATCHES,
-            max_time: Duration::from_millis(MAX_SEARCH_TIME_MS),
-            current_matches: 0,
-        }
-    }
-
-    fn should_continue(&self) -> bool {
-        self.current_matches < self.max_matches && self.start_time.elapsed() < self.max_time
-    }
+/// Point the bash/zsh function at the binary and replace its flag list with the flag table
+fn shell_function_script(binary: &str) -> String {
+    let mut script = SHELL_FUNCTION_SCRIPT.replace(SHELL_BINARY_DEFAULT, binary);
+    if let Some(start) = script.find(SHELL_FLAGS_LINE) {
+        let end = script[start..]
+            .find('\n')
+            .map_or(script.len(), |end| start + end);
+        let flags_line = format!("{}{})", SHELL_FLAGS_LINE, passthrough_flags().join(" "));
+        script.replace_range(start..end, &flags_line);
+    }
+    script
+}
 
-    fn add_match(&mut self) {
-        self.current_matches += 1;
-    }
+/// Fill in the fish template from the binary path and the pass-through flag table
+fn fish_init_script(binary: &str) -> String {
+    let flag_completions: Vec<String> = PASSTHROUGH_FLAGS
+        .iter()
+        .map(|(short, long, description)| {
+            let mut completion = String::from("complete -c jcd");
+            if let Some(short) = short.strip_prefix('-') {
+                completion.push_str(&format!(" -s {}", short));
+            }
+            if let Some(long) = long.strip_prefix("--") {
+                completion.push_str(&format!(" -l {}", long));
+            }
+            format!("{} -d '{}'", completion, description)
+        })
+        .collect();
+
+    FISH_SCRIPT_TEMPLATE
+        .replace("@BINARY@", binary)
+        .replace("@FLAGS@", &passthrough_flags().join(" "))
+        .replace("@FLAG_COMPLETIONS@", &flag_completions.join("\n"))
 }
 
-/// Resolves the search context by handling relative paths and directory navigation patterns.
-/// Returns (search_directory, pattern) where search_directory is the resolved starting point
-/// and pattern is the remaining search term after resolving relative components.
-fn resolve_search_context(current_dir: &Path, search_term: &str) -> (PathBuf, String) {
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: resolve_search_context: current_dir={}, search_term='{}'",
-            current_dir.display(),
-            search_term
-        );
-    }
+fn main() {
+    let args: Vec<String> = env::args().collect();
 
-    // Handle empty search term
-    if search_term.is_empty() {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Empty search term, returning current directory");
-        }
-        return (current_dir.to_path_buf(), String::new());
+    if args.len() < 2 {
+        eprintln!("Error: No search term provided");
+        process::exit(1);
     }
 
-    // Handle pure directory navigation without search pattern
-    if search_term == ".." {
-        if let Some(parent) = current_dir.parent() {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Parent directory navigation to {}", parent.display());
-            }
-            return (parent.to_path_buf(), String::new());
-        } else {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Already at root, staying in current directory");
-            }
-            return (current_dir.to_path_buf(), String::new());
+    // `jcd --record <dir>` counts a visit; the shell wrapper calls it after each successful jump
+    if args.len() == 3 && args[1] == "--record" {
+        if let Err(e) = record_visit(Path::new(&args[2])) {
+            eprintln!("Error: Cannot record visit to {}: {}", args[2], e);
+            process::exit(1);
         }
+        return;
     }
 
-    if search_term == "." {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Current directory navigation, staying put");
+    // Bookmark commands: `jcd --mark <name>`, `jcd --marks`, `jcd --unmark <name>`
+    if args.len() == 3 && args[1] == "--mark" {
+        if let Err(e) = mark_current_dir(&args[2]) {
+            eprintln!("Error: Cannot bookmark '{}': {}", args[2], e);
+            process::exit(1);
         }
-        return (current_dir.to_path_buf(), String::new());
+        return;
     }
-
-    // Handle relative paths with patterns like "../foo", "../../bar", etc.
-    if search_term.starts_with("../") || search_term.starts_with("./") {
-        let path = Path::new(search_term);
-        let mut resolved_dir = current_dir.to_path_buf();
-        let mut remaining_pattern = String::new();
-
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Processing relative path pattern");
-        }
-
-        for component in path.components() {
-            match component {
-                std::path::Component::CurDir => {
-                    if is_debug_enabled() {
-                        eprintln!(
-                            "DEBUG: Current dir component, staying in {}",
-                            resolved_dir.display()
-                        );
-                    }
-                    continue;
-                }
-                std::path::Component::ParentDir => {
-                    if let Some(parent) = resolved_dir.parent() {
-                        if is_debug_enabled() {
-                            eprintln!(
-                                "DEBUG: Parent dir component, moving from {} to {}",
-                                resolved_dir.display(),
-                                parent.display()
-                            );
-                        }
-                        resolved_dir = parent.to_path_buf();
-                    }
-                }
-                std::path::Component::Normal(name) => {
-                    remaining_pattern = name.to_string_lossy().to_string();
-                    if is_debug_enabled() {
-                        eprintln!(
-                            "DEBUG: Found search pattern '{}' in relative path",
-                            remaining_pattern
-                        );
-                    }
-                    break;
-                }
-                _ => {
-                    if is_debug_enabled() {
-                        eprintln!("DEBUG: Other path component encountered");
-                    }
-                    break;
-                }
-            }
+    if args.len() == 2 && args[1] == "--marks" {
+        list_bookmarks();
+        return;
+    }
+    if args.len() == 3 && args[1] == "--unmark" {
+        let mut store = BookmarkStore::load();
+        if !store.remove(&args[2]) {
+            eprintln!("Error: No bookmark named '{}'", args[2]);
+            process::exit(1);
         }
-
-        if is_debug_enabled() {
-            eprintln!(
-                "DEBUG: Resolved relative path: search_dir={}, pattern='{}'",
-                resolved_dir.display(),
-                remaining_pattern
-            );
+        if let Err(e) = store.save() {
+            eprintln!("Error: Cannot save bookmarks: {}", e);
+            process::exit(1);
         }
-        return (resolved_dir, remaining_pattern);
+        return;
     }
 
-    // Handle multiple levels of parent directory navigation like "../../", "../../../"
-    if search_term.chars().all(|c| c == '.' || c == '/') && search_term.contains("..") {
-        let mut resolved_dir = current_dir.to_path_buf();
-        let path = Path::new(search_term);
-
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Processing multiple parent directory navigation");
-        }
-
-        for component in path.components() {
-            match component {
-                std::path::Component::ParentDir => {
-                    if let Some(parent) = resolved_dir.parent() {
-                        if is_debug_enabled() {
-                            eprintln!(
-                                "DEBUG: Moving up from {} to {}",
-                                resolved_dir.display(),
-                                parent.display()
-                            );
-                        }
-                        resolved_dir = parent.to_path_buf();
-                    }
-                }
-                std::path::Component::CurDir => {
-                    if is_debug_enabled() {
-                        eprintln!("DEBUG: Staying in current directory");
-                    }
-                }
-                _ => {
-                    break;
-                }
+    // `jcd init <shell>` prints the shell integration instead of searching.
+    // A numeric second argument is a search term, so `jcd init 2` still searches.
+    if args.len() == 3 && args[1] == "init" && args[2].parse::<usize>().is_err() {
+        match shell_init_script(&args[2]) {
+            Ok(script) => {
+                print!("{}", script);
+                return;
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(1);
             }
         }
-
-        if is_debug_enabled() {
-            eprintln!(
-                "DEBUG: Final resolved directory: {}",
-                resolved_dir.display()
-            );
-        }
-        return (resolved_dir, String::new());
     }
 
-    // For absolute paths and regular patterns, use the original behavior
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: Using current directory for search with pattern '{}'",
-            search_term
-        );
+    // `jcd index build|status|clear [dir]` manages the directory index
+    if (3..=4).contains(&args.len())
+        && args[1] == "index"
+        && matches!(args[2].as_str(), "build" | "status" | "clear")
+    {
+        if let Err(e) = index_command(&args[2], args.get(3).map(String::as_str)) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return;
     }
-    (current_dir.to_path_buf(), search_term.to_string())
-}
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() < 2 {
-        eprintln!("Error: No search term provided");
-        process::exit(1);
+    // `jcd daemon watch [dir...]` keeps the directory listings below its roots current
+    if args.len() >= 3 && args[1] == "daemon" && args[2] == "watch" {
+        if let Err(e) = daemon_command(&args[3..]) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return;
     }
 
     // Parse command line arguments for flags
-    let mut case_sensitive = true; // Default to case sensitive
-    let mut search_term = String::new();
-    let mut tab_index = 0;
+    let mut case_policy = None; // -i / -s / -S, otherwise the config default or smart case
+    let mut fuzzy = false; // -f enables fuzzy subsequence matching
+    let mut acronym = false; // -w matches pattern characters against word initials
+    let mut normalization = None; // -u / -a / --no-unicode, otherwise the config default
+    let mut mode = None; // -F / -r / -g pick the pattern mode, otherwise globs are auto-detected
+    let mut terms: Vec<String> = Vec::new();
+    let mut tab_index = None; // --index N picks a match from the ranked list
+    let mut threads = None; // -j N sizes the parallel directory walker
+    let mut budgets = Vec::new(); // --max-<budget> N limits the search
     let mut quiet_mode = false;
     let mut bypass_ignore = false; // -x flag to bypass ignore patterns
+    let mut list_mode = false; // --list prints every ranked match in one invocation
+    let mut explain = false; // --explain reports how candidates were ranked and dropped
+    let mut use_index = true; // --no-index lists every directory from disk
+    let mut output_format = OutputFormat::Plain;
 
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
             "-i" => {
-                case_sensitive = false; // -i flag makes it case insensitive
+                case_policy = Some(CasePolicy::Insensitive);
+                i += 1;
+            }
+            "-s" | "--smart-case" => {
+                case_policy = Some(CasePolicy::Smart);
+                i += 1;
+            }
+            "-S" | "--case-sensitive" => {
+                case_policy = Some(CasePolicy::Sensitive);
                 i += 1;
             }
             "-x" => {
                 bypass_ignore = true; // -x flag bypasses ignore patterns
                 i += 1;
             }
+            "-f" | "--fuzzy" => {
+                fuzzy = true;
+                i += 1;
+            }
+            "--no-fuzzy" => {
+                fuzzy = false;
+                i += 1;
+            }
+            "-w" | "--acronym" => {
+                acronym = true;
+                i += 1;
+            }
+            "--no-acronym" => {
+                acronym = false;
+                i += 1;
+            }
+            "-u" | "--unicode" => {
+                normalization = Some(Normalization::Unicode);
+                i += 1;
+            }
+            "-a" | "--ignore-accents" => {
+                normalization = Some(Normalization::IgnoreAccents);
+                i += 1;
+            }
+            "--no-unicode" => {
+                normalization = Some(Normalization::None);
+                i += 1;
+            }
+            "-r" | "--regex" => {
+                mode = Some(PatternMode::Regex);
+                i += 1;
+            }
+            "-g" | "--glob" => {
+                mode = Some(PatternMode::Glob);
+                i += 1;
+            }
+            "-F" | "--fixed" => {
+                mode = Some(PatternMode::Literal);
+                i += 1;
+            }
             "--quiet" => {
                 quiet_mode = true;
                 i += 1;
             }
-            arg => {
-                if search_term.is_empty() {
-                    search_term = arg.to_string();
-                } else if tab_index == 0 {
-                    tab_index = arg.parse::<usize>().unwrap_or(0);
+            "--list" => {
+                list_mode = true;
+                i += 1;
+            }
+            "--explain" => {
+                explain = true;
+                i += 1;
+            }
+            "--no-index" => {
+                use_index = false;
+                i += 1;
+            }
+            "--json" => {
+                output_format = OutputFormat::Json;
+                i += 1;
+            }
+            "-0" | "--null" => {
+                output_format = OutputFormat::Nul;
+                i += 1;
+            }
+            "--shell-quote" => {
+                output_format = OutputFormat::Shell;
+                i += 1;
+            }
+            "--index" => {
+                match args
+                    .get(i + 1)
+                    .and_then(|index| index.parse::<usize>().ok())
+                {
+                    Some(index) => tab_index = Some(index),
+                    None => {
+                        eprintln!("Error: --index expects a number");
+                        process::exit(1);
+                    }
                 }
+                i += 2;
+            }
+            "-j" | "--threads" => {
+                match args
+                    .get(i + 1)
+                    .and_then(|threads| threads.parse::<usize>().ok())
+                {
+                    Some(count) => threads = Some(count),
+                    None => {
+                        eprintln!("Error: --threads expects a number");
+                        process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            arg if arg
+                .strip_prefix("--max-")
+                .is_some_and(|name| budget::NAMES.contains(&name)) =>
+            {
+                match args.get(i + 1) {
+                    Some(value) => budgets.push((arg["--max-".len()..].to_string(), value)),
+                    None => {
+                        eprintln!("Error: {} expects a number", arg);
+                        process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            arg => {
+                terms.push(arg.to_string());
                 i += 1;
             }
         }
     }
 
-    if search_term.is_empty() {
+    let tab_index = tab_index.unwrap_or(0);
+
+    if terms.is_empty() {
         eprintln!("Error: No search term provided");
         process::exit(1);
     }
+    let search_term = terms.join(" ");
+
+    let config = Config::load();
+    // The walker's thread count: -j, then JCD_THREADS, then the config file, else one per CPU
+    let threads = threads
+        .or_else(|| env::var("JCD_THREADS").ok()?.parse().ok())
+        .or(config.threads);
+    if let Some(threads) = threads {
+        walk::set_threads(threads);
+    }
+    // Search budgets likewise: --max-<budget>, then JCD_MAX_<BUDGET>, then search.<budget>
+    let mut search_budget = config.budget;
+    for name in budget::NAMES {
+        let var = format!("JCD_MAX_{}", name.to_uppercase().replace('-', "_"));
+        if let Ok(value) = env::var(&var) {
+            if !search_budget.set(name, &value) && is_debug_enabled() {
+                eprintln!("DEBUG: Ignoring {}='{}'", var, value);
+            }
+        }
+    }
+    for (name, value) in &budgets {
+        if !search_budget.set(name, value) {
+            eprintln!("Error: Invalid value '{}' for --max-{}", value, name);
+            process::exit(1);
+        }
+    }
+    let options = SearchOptions {
+        case: case_policy.or(config.case).unwrap_or(CasePolicy::Smart),
+        fuzzy,
+        acronym,
+        mode,
+        normalization: normalization
+            .or(config.normalize)
+            .unwrap_or(Normalization::None),
+        bypass_ignore,
+        budget: search_budget,
+        ranking: config.ranking,
+        use_index,
+        explain,
+        ..SearchOptions::default()
+    };
 
     let current_dir = match env::current_dir() {
         Ok(dir) => dir,
@@ -375,59 +644,189 @@ fn main() {
             process::exit(1);
         }
     };
+    let searcher = Searcher::new(options);
+
+    // Search in a background thread with a busy indicator, unless in quiet mode
+    let results = if quiet_mode {
+        searcher.search(&terms, &current_dir)
+    } else {
+        let searcher = searcher.clone(); // Clone for thread
+        search_with_progress(move || searcher.search(&terms, &current_dir))
+    };
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    };
 
-    // Handle relative paths and standard directory navigation
-    let (search_dir, pattern) = resolve_search_context(&current_dir, &search_term);
+    // --explain prints the report in place of the matches
+    if explain {
+        let stdout = io::stdout();
+        let ranking = &searcher.options().ranking;
+        if explain::report(&mut stdout.lock(), &search_term, &results, ranking).is_err() {
+            process::exit(1);
+        }
+        process::exit(if results.matches.is_empty() { 1 } else { 0 });
+    }
+    let matches = results.matches;
 
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: Searching for '{}' from {}",
-            pattern,
-            search_dir.display()
-        );
+    // A budget that ran out leaves the list incomplete, so say which one
+    if !quiet_mode {
+        if let Some(limit) = results.cut_short {
+            eprintln!(
+                "jcd: search hit {}, results may be incomplete (see {})",
+                searcher.options().budget.describe(limit),
+                limit.flag()
+            );
+        }
     }
 
-    // Load ignore patterns unless bypassed
-    let ignore_patterns = if bypass_ignore {
+    if matches.is_empty() || tab_index >= matches.len() {
         if is_debug_enabled() {
-            eprintln!("DEBUG: Bypassing ignore patterns (-x flag)");
+            eprintln!("DEBUG: No matches or index out of range");
         }
-        Vec::new()
-    } else {
-        load_ignore_patterns()
-    };
+        process::exit(1);
+    }
 
-    // Use threaded search with busy indicator (unless in quiet mode)
-    let matches = if quiet_mode {
-        find_matching_directories(&search_dir, &pattern, case_sensitive, &ignore_patterns)
+    // In list mode emit the whole ranked list so callers can cycle without re-searching
+    let selected = if list_mode {
+        &matches[..]
     } else {
-        search_with_progress(&search_dir, &pattern, case_sensitive, &ignore_patterns)
+        &matches[tab_index..=tab_index]
     };
 
-    if is_debug_enabled() {
-        eprintln!("DEBUG: Found {} matches", matches.len());
+    // A typo match is a guess, so say where we are going instead
+    if !list_mode && !quiet_mode && selected[0].match_quality == MatchQuality::Typo {
+        let name = selected[0]
+            .path
+            .file_name()
+            .map_or(Cow::Borrowed(""), |name| name.to_string_lossy());
+        eprintln!(
+            "jcd: no match for '{}', did you mean '{}'?",
+            search_term, name
+        );
     }
 
-    if matches.is_empty() || tab_index >= matches.len() {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: No matches or index out of range");
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for m in selected {
+        let written = match output_format {
+            OutputFormat::Plain => out
+                .write_all(&path_bytes(&m.path))
+                .and_then(|_| out.write_all(b"\n")),
+            OutputFormat::Json => writeln!(out, "{}", match_to_json(m, &searcher)),
+            OutputFormat::Nul => out
+                .write_all(&path_bytes(&m.path))
+                .and_then(|_| out.write_all(b"\0")),
+            OutputFormat::Shell => writeln!(out, "{}", shell_quote(&path_bytes(&m.path))),
+        };
+        if written.is_err() {
+            break;
         }
-        process::exit(1);
     }
+}
 
-    println!("{}", matches[tab_index].path.display());
+/// Quote raw bytes as a single bash/zsh word that evaluates back to the same bytes
+fn shell_quote(bytes: &[u8]) -> String {
+    // Printable UTF-8 can use plain single quotes, which need only the quote itself escaped
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        if !text.chars().any(char::is_control) {
+            return format!("'{}'", text.replace('\'', "'\\''"));
+        }
+    }
+
+    // Everything else goes through ANSI-C quoting with hex escapes for unprintable bytes
+    let mut quoted = String::from("$'");
+    for &byte in bytes {
+        match byte {
+            b'\\' => quoted.push_str("\\\\"),
+            b'\'' => quoted.push_str("\\'"),
+            b'\n' => quoted.push_str("\\n"),
+            b'\t' => quoted.push_str("\\t"),
+            0x20..=0x7e => quoted.push(byte as char),
+            _ => quoted.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    quoted.push('\'');
+    quoted
 }
 
-fn search_with_progress(
-    current_dir: &Path,
-    search_term: &str,
-    case_sensitive: bool,
-    ignore_patterns: &[Regex],
-) -> Vec<DirectoryMatch> {
-    let current_dir = current_dir.to_path_buf();
-    let search_term = search_term.to_string();
-    let ignore_patterns = ignore_patterns.to_vec(); // Clone for thread
+/// Quote raw bytes as a single fish word that evaluates back to the same bytes
+fn fish_quote(bytes: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        if !text.chars().any(char::is_control) {
+            return format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"));
+        }
+    }
 
+    // fish has no ANSI-C quoting, but unquoted \XHH escapes produce arbitrary bytes
+    let mut quoted = String::new();
+    for &byte in bytes {
+        match byte {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'/' | b'.' | b'-' | b'_' => {
+                quoted.push(byte as char)
+            }
+            _ => quoted.push_str(&format!("\\X{:02x}", byte)),
+        }
+    }
+    quoted
+}
+
+/// Escape a string for inclusion in a JSON document
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    for ch in value.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Serialize a match and the metadata that explains its ranking as a single-line JSON object.
+/// Paths that are not valid UTF-8 are flagged with `path_lossy` and carry their raw bytes.
+fn match_to_json(m: &DirectoryMatch, searcher: &Searcher) -> String {
+    let spans: Vec<String> = m
+        .match_spans
+        .iter()
+        .map(|(start, end)| format!("[{},{}]", start, end))
+        .collect();
+    let bytes = path_bytes(&m.path);
+    let raw = match std::str::from_utf8(&bytes) {
+        Ok(_) => String::new(),
+        Err(_) => {
+            let bytes: Vec<String> = bytes.iter().map(u8::to_string).collect();
+            format!(",\"path_lossy\":true,\"path_bytes\":[{}]", bytes.join(","))
+        }
+    };
+    format!(
+        "{{\"path\":\"{}\"{},\"depth_from_current\":{},\"match_quality\":\"{:?}\",\"priority\":{},\"frecency\":{:.3},\"score\":{},\"spread\":{},\"match_spans\":[{}]}}",
+        json_escape(&m.path.to_string_lossy()),
+        raw,
+        m.depth_from_current,
+        m.match_quality,
+        searcher.options().ranking.priority(m),
+        m.frecency,
+        m.score,
+        m.spread,
+        spans.join(",")
+    )
+}
+
+/// Run `search` in a background thread, showing a busy indicator if it takes a while
+fn search_with_progress<T, F>(search: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
     // Shared state for the search result
     let result = Arc::new(Mutex::new(None));
     let result_clone = Arc::clone(&result);
@@ -438,8 +837,7 @@ fn search_with_progress(
 
     // Start the search in a background thread
     let search_handle = thread::spawn(move || {
-        let matches =
-            find_matching_directories(&current_dir, &search_term, case_sensitive, &ignore_patterns);
+        let matches = search();
 
         // Store the result
         {
@@ -485,8 +883,8 @@ fn search_with_progress(
     }
 
     // Return the result
-    let result_guard = result.lock().unwrap();
-    result_guard.as_ref().unwrap().clone()
+    let mut result_guard = result.lock().unwrap();
+    result_guard.take().unwrap()
 }
 
 fn show_busy_indicator(search_complete: &Arc<Mutex<bool>>) {
@@ -513,1017 +911,3 @@ fn show_busy_indicator(search_complete: &Arc<Mutex<bool>>) {
         thread::sleep(Duration::from_millis(200));
     }
 }
-
-fn find_matching_directories(
-    current_dir: &Path,
-    search_term: &str,
-    case_sensitive: bool,
-    ignore_patterns: &[Regex],
-) -> Vec<DirectoryMatch> {
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: find_matching_directories: current_dir={}, search_term='{}', case_sensitive={}",
-            current_dir.display(),
-            search_term,
-            case_sensitive
-        );
-    }
-
-    let mut matches = Vec::new();
-
-    // Handle empty search term (pure directory navigation like "..", "../../")
-    if search_term.is_empty() {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Empty search term, returning current directory as match");
-        }
-        matches.push(DirectoryMatch {
-            path: current_dir.to_path_buf(),
-            depth_from_current: 0,
-            match_quality: MatchQuality::ExactDown,
-        });
-        return matches;
-    }
-
-    // Handle absolute paths
-    if search_term.starts_with('/') {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Processing absolute path: {}", search_term);
-        }
-        let path = Path::new(search_term);
-
-        if search_term.ends_with('/') {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Absolute path ends with slash - exploring subdirectories");
-            }
-            let dir_path = Path::new(&search_term[..search_term.len() - 1]);
-            if dir_path.exists() && dir_path.is_dir() {
-                let mut subdir_matches = Vec::new();
-                search_absolute_pattern(dir_path, "", &mut subdir_matches, case_sensitive);
-
-                if !subdir_matches.is_empty() {
-                    if is_debug_enabled() {
-                        eprintln!(
-                            "DEBUG: Found {} subdirectories in {}",
-                            subdir_matches.len(),
-                            dir_path.display()
-                        );
-                    }
-                    matches.extend(subdir_matches);
-                } else {
-                    if is_debug_enabled() {
-                        eprintln!("DEBUG: No subdirectories found, returning directory itself");
-                    }
-                    matches.push(DirectoryMatch {
-                        path: dir_path.to_path_buf(),
-                        depth_from_current: 0,
-                        match_quality: MatchQuality::ExactDown,
-                    });
-                }
-            } else {
-                if is_debug_enabled() {
-                    eprintln!("DEBUG: Directory doesn't exist, treating as pattern search");
-                }
-                let search_term_no_slash = &search_term[..search_term.len() - 1];
-                let (search_root, search_pattern) =
-                    find_search_root_and_pattern(search_term_no_slash);
-                if let Some(root) = search_root {
-                    search_absolute_pattern(&root, &search_pattern, &mut matches, case_sensitive);
-                }
-            }
-        } else if path.exists() && path.is_dir() {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Absolute path exists exactly, returning it directly");
-            }
-            matches.push(DirectoryMatch {
-                path: path.to_path_buf(),
-                depth_from_current: 0,
-                match_quality: MatchQuality::ExactDown,
-            });
-        } else {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Absolute path doesn't exist, finding search root and pattern");
-            }
-            let (search_root, search_pattern) = find_search_root_and_pattern(search_term);
-            if let Some(root) = search_root {
-                if is_debug_enabled() {
-                    eprintln!(
-                        "DEBUG: Searching from root {} for pattern '{}'",
-                        root.display(),
-                        search_pattern
-                    );
-                }
-                search_absolute_pattern(&root, &search_pattern, &mut matches, case_sensitive);
-            }
-        }
-        return finalize_matches(matches);
-    }
-
-    // Handle path-like patterns (contains '/')
-    if search_term.contains('/') {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Processing path-like pattern with '/'");
-        }
-        let mut context = SearchContext::new();
-        search_path_pattern_fast(
-            current_dir,
-            search_term,
-            &mut matches,
-            &mut context,
-            case_sensitive,
-        );
-        if !matches.is_empty() {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Found {} matches for path pattern", matches.len());
-            }
-            return finalize_matches(matches);
-        }
-    }
-
-    if is_debug_enabled() {
-        eprintln!("DEBUG: Starting comprehensive search - up tree then down tree");
-    }
-
-    // 1. Search up for exact matches, then partial matches (direct path to root only)
-    let up_matches =
-        search_up_tree_with_priority(current_dir, search_term, case_sensitive, ignore_patterns);
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: Found {} matches searching up tree",
-            up_matches.len()
-        );
-    }
-    matches.extend(up_matches);
-
-    // 2. Search down for all matches (exact and partial) from current directory only
-    let down_matches =
-        search_down_breadth_first_all(current_dir, search_term, case_sensitive, ignore_patterns);
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: Found {} matches searching down tree",
-            down_matches.len()
-        );
-    }
-    matches.extend(down_matches);
-
-    // Return all matches sorted by priority
-    if !matches.is_empty() {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Total {} matches found, finalizing", matches.len());
-        }
-        return finalize_matches(matches);
-    }
-
-    if is_debug_enabled() {
-        eprintln!("DEBUG: No matches found");
-    }
-    Vec::new()
-}
-
-fn search_up_tree_with_priority(
-    current_dir: &Path,
-    search_term: &str,
-    case_sensitive: bool,
-    ignore_patterns: &[Regex],
-) -> Vec<DirectoryMatch> {
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_up_tree_with_priority: searching for '{}', case_sensitive={}",
-            search_term, case_sensitive
-        );
-    }
-
-    let mut exact_matches = Vec::new();
-    let mut partial_matches = Vec::new();
-    let mut current = current_dir;
-    let mut depth = -1;
-
-    let search_lower = if case_sensitive {
-        search_term.to_string()
-    } else {
-        search_term.to_lowercase()
-    };
-
-    while let Some(parent) = current.parent() {
-        if let Some(name) = parent.file_name() {
-            let name_str = name.to_string_lossy();
-
-            // Check if this directory should be ignored
-            if should_ignore_directory(&name_str, ignore_patterns) {
-                if is_debug_enabled() {
-                    eprintln!("DEBUG: Ignoring parent directory: {}", name_str);
-                }
-                current = parent;
-                depth -= 1;
-                continue;
-            }
-
-            let (name_compare, search_compare) = if case_sensitive {
-                (name_str.to_string(), search_term.to_string())
-            } else {
-                (name_str.to_lowercase(), search_lower.clone())
-            };
-
-            if is_debug_enabled() {
-                eprintln!("DEBUG: Checking parent '{}' at depth {}", name_str, depth);
-            }
-
-            if name_compare == search_compare {
-                if is_debug_enabled() {
-                    eprintln!("DEBUG: Exact match found: {}", parent.display());
-                }
-                exact_matches.push(DirectoryMatch {
-                    path: parent.to_path_buf(),
-                    depth_from_current: depth,
-                    match_quality: MatchQuality::ExactUp,
-                });
-            } else if name_compare.contains(&search_compare) {
-                if is_debug_enabled() {
-                    eprintln!("DEBUG: Partial match found: {}", parent.display());
-                }
-                partial_matches.push(DirectoryMatch {
-                    path: parent.to_path_buf(),
-                    depth_from_current: depth,
-                    match_quality: MatchQuality::PartialUp,
-                });
-            }
-        }
-        current = parent;
-        depth -= 1;
-    }
-
-    let mut result = exact_matches;
-    result.extend(partial_matches);
-
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_up_tree_with_priority completed with {} matches",
-            result.len()
-        );
-    }
-
-    result
-}
-
-fn search_down_breadth_first_all(
-    current_dir: &Path,
-    search_term: &str,
-    case_sensitive: bool,
-    ignore_patterns: &[Regex],
-) -> Vec<DirectoryMatch> {
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_down_breadth_first_all: searching for '{}', case_sensitive={}",
-            search_term, case_sensitive
-        );
-    }
-
-    use std::collections::VecDeque;
-
-    let mut queue = VecDeque::new();
-    let mut all_matches = Vec::new();
-    queue.push_back((current_dir.to_path_buf(), 0));
-    let search_lower = if case_sensitive {
-        search_term.to_string()
-    } else {
-        search_term.to_lowercase()
-    };
-    let max_depth = 8;
-
-    // First, search immediate subdirectories (depth 1) to check for early stopping
-    let mut immediate_matches = Vec::new();
-
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: Processing immediate subdirectories in {}",
-            current_dir.display()
-        );
-    }
-
-    // Process current directory (depth 0) first
-    if let Ok(entries) = fs::read_dir(current_dir) {
-        let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-        entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
-
-        for entry in &entries {
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_dir() {
-                    let path = entry.path();
-                    if let Some(name) = path.file_name() {
-                        let name_str = name.to_string_lossy();
-
-                        // Check if this directory should be ignored
-                        if should_ignore_directory(&name_str, ignore_patterns) {
-                            if is_debug_enabled() {
-                                eprintln!("DEBUG: Ignoring directory: {}", name_str);
-                            }
-                            continue;
-                        }
-
-                        let (name_compare, search_compare) = if case_sensitive {
-                            (name_str.to_string(), search_term.to_string())
-                        } else {
-                            (name_str.to_lowercase(), search_lower.clone())
-                        };
-
-                        // Check for any match in immediate subdirectories
-                        if name_compare == search_compare {
-                            if is_debug_enabled() {
-                                eprintln!("DEBUG: Immediate exact match: {}", path.display());
-                            }
-                            let dir_match = DirectoryMatch {
-                                path: path.clone(),
-                                depth_from_current: 1,
-                                match_quality: MatchQuality::ExactDown,
-                            };
-                            immediate_matches.push(dir_match.clone());
-                            all_matches.push(dir_match);
-                        } else if name_compare.starts_with(&search_compare) {
-                            if is_debug_enabled() {
-                                eprintln!("DEBUG: Immediate prefix match: {}", path.display());
-                            }
-                            let dir_match = DirectoryMatch {
-                                path: path.clone(),
-                                depth_from_current: 1,
-                                match_quality: MatchQuality::PrefixDown,
-                            };
-                            immediate_matches.push(dir_match.clone());
-                            all_matches.push(dir_match);
-                        } else if name_compare.contains(&search_compare) {
-                            if is_debug_enabled() {
-                                eprintln!("DEBUG: Immediate partial match: {}", path.display());
-                            }
-                            let dir_match = DirectoryMatch {
-                                path: path.clone(),
-                                depth_from_current: 1,
-                                match_quality: MatchQuality::PartialDown,
-                            };
-                            immediate_matches.push(dir_match.clone());
-                            all_matches.push(dir_match);
-                        }
-
-                        // Add subdirectories to queue for potential deeper search
-                        queue.push_back((path.clone(), 1));
-                    }
-                }
-            }
-        }
-    }
-
-    // If there are exact or prefix matches in immediate subdirectories, return early to avoid deep search
-    let has_good_immediate = immediate_matches.iter().any(|m| {
-        matches!(
-            m.match_quality,
-            MatchQuality::ExactDown | MatchQuality::PrefixDown
-        )
-    });
-    if has_good_immediate {
-        if is_debug_enabled() {
-            eprintln!("DEBUG: Found good immediate matches, skipping deep search");
-        }
-        return finalize_matches(all_matches);
-    }
-
-    if is_debug_enabled() {
-        eprintln!("DEBUG: No good immediate matches, continuing with deep search");
-    }
-
-    // Otherwise, continue with breadth-first search for deeper levels
-    while let Some((current_path, depth)) = queue.pop_front() {
-        if depth == 0 || depth > max_depth {
-            continue; // Skip depth 0 (already processed) and beyond max depth
-        }
-        if is_debug_enabled() {
-            eprintln!(
-                "DEBUG: Searching depth {} in {}",
-                depth,
-                current_path.display()
-            );
-        }
-
-        let mut level_matches = Vec::new();
-        let mut level_subdirs = Vec::new();
-
-        if let Ok(entries) = fs::read_dir(&current_path) {
-            // Collect and sort entries for deterministic order
-            let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-            entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
-
-            // Process all entries at this level
-            for entry in &entries {
-                if let Ok(metadata) = entry.metadata() {
-                    if metadata.is_dir() {
-                        let path = entry.path();
-                        if let Some(name) = path.file_name() {
-                            let name_str = name.to_string_lossy();
-
-                            // Check if this directory should be ignored
-                            if should_ignore_directory(&name_str, ignore_patterns) {
-                                if is_debug_enabled() {
-                                    eprintln!(
-                                        "DEBUG: Ignoring directory at depth {}: {}",
-                                        depth + 1,
-                                        name_str
-                                    );
-                                }
-                                continue;
-                            }
-
-                            let (name_compare, search_compare) = if case_sensitive {
-                                (name_str.to_string(), search_term.to_string())
-                            } else {
-                                (name_str.to_lowercase(), search_lower.clone())
-                            };
-
-                            // Check for any match (exact, prefix, or partial)
-                            if name_compare == search_compare {
-                                if is_debug_enabled() {
-                                    eprintln!(
-                                        "DEBUG: Deep exact match at depth {}: {}",
-                                        depth + 1,
-                                        path.display()
-                                    );
-                                }
-                                level_matches.push(DirectoryMatch {
-                                    path: path.clone(),
-                                    depth_from_current: (depth + 1) as i32,
-                                    match_quality: MatchQuality::ExactDown,
-                                });
-                            } else if name_compare.starts_with(&search_compare) {
-                                if is_debug_enabled() {
-                                    eprintln!(
-                                        "DEBUG: Deep prefix match at depth {}: {}",
-                                        depth + 1,
-                                        path.display()
-                                    );
-                                }
-                                level_matches.push(DirectoryMatch {
-                                    path: path.clone(),
-                                    depth_from_current: (depth + 1) as i32,
-                                    match_quality: MatchQuality::PrefixDown,
-                                });
-                            } else if name_compare.contains(&search_compare) {
-                                if is_debug_enabled() {
-                                    eprintln!(
-                                        "DEBUG: Deep partial match at depth {}: {}",
-                                        depth + 1,
-                                        path.display()
-                                    );
-                                }
-                                level_matches.push(DirectoryMatch {
-                                    path: path.clone(),
-                                    depth_from_current: (depth + 1) as i32,
-                                    match_quality: MatchQuality::PartialDown,
-                                });
-                            }
-
-                            // Collect subdirectories for next level
-                            if depth < max_depth {
-                                level_subdirs.push((path.clone(), depth + 1));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Add matches from this level
-        all_matches.extend(level_matches);
-
-        // Add subdirectories to queue for next level search
-        for (subdir, next_depth) in level_subdirs {
-            queue.push_back((subdir, next_depth));
-        }
-    }
-
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_down_breadth_first_all completed with {} total matches",
-            all_matches.len()
-        );
-    }
-
-    finalize_matches(all_matches)
-}
-
-fn finalize_matches(mut matches: Vec<DirectoryMatch>) -> Vec<DirectoryMatch> {
-    if is_debug_enabled() {
-        eprintln!("DEBUG: finalize_matches: input {} matches", matches.len());
-        for (i, m) in matches.iter().enumerate() {
-            eprintln!(
-                "DEBUG:   [{}] {:?} depth={} path={}",
-                i,
-                m.match_quality,
-                m.depth_from_current,
-                m.path.display()
-            );
-        }
-    }
-
-    // Remove duplicates based on path
-    matches.sort_by(|a, b| a.path.cmp(&b.path));
-    matches.dedup_by(|a, b| a.path == b.path);
-
-    if is_debug_enabled() {
-        eprintln!("DEBUG: After dedup: {} matches", matches.len());
-    }
-
-    // Sort by priority with clear prioritization
-    matches.sort_by(|a, b| {
-        // Define priority categories
-        let get_priority = |m: &DirectoryMatch| -> u32 {
-            match (m.depth_from_current, &m.match_quality) {
-                // Immediate subdirectory exact matches - highest priority
-                (1, MatchQuality::ExactDown) => 0,
-                // Immediate subdirectory prefix matches - very high priority
-                (1, MatchQuality::PrefixDown) => 1,
-                // Immediate subdirectory partial matches - high priority
-                (1, MatchQuality::PartialDown) => 2,
-                // Up tree exact matches - medium-high priority
-                (_, MatchQuality::ExactUp) => 3,
-                // Up tree partial matches - medium priority
-                (_, MatchQuality::PartialUp) => 4,
-                // Deeper exact matches - lower priority
-                (_, MatchQuality::ExactDown) => 5,
-                // Deeper prefix matches - lower priority
-                (_, MatchQuality::PrefixDown) => 6,
-                // Deeper partial matches - lowest priority
-                (_, MatchQuality::PartialDown) => 7,
-            }
-        };
-
-        let a_priority = get_priority(a);
-        let b_priority = get_priority(b);
-
-        // First sort by priority
-        let priority_cmp = a_priority.cmp(&b_priority);
-        if priority_cmp != std::cmp::Ordering::Equal {
-            return priority_cmp;
-        }
-
-        // Within same priority, sort by depth (shallower first for down matches, closer first for up matches)
-        match a.match_quality {
-            MatchQuality::ExactUp | MatchQuality::PartialUp => {
-                // For up matches, closer to current (higher depth) comes first
-                b.depth_from_current.cmp(&a.depth_from_current)
-            }
-            _ => {
-                // For down matches, shallower (lower depth) comes first
-                a.depth_from_current.cmp(&b.depth_from_current)
-            }
-        }
-    });
-
-    if is_debug_enabled() {
-        eprintln!("DEBUG: After sorting: {} matches", matches.len());
-        for (i, m) in matches.iter().enumerate() {
-            eprintln!(
-                "DEBUG:   [{}] {:?} depth={} path={}",
-                i,
-                m.match_quality,
-                m.depth_from_current,
-                m.path.display()
-            );
-        }
-    }
-
-    matches
-}
-
-fn search_path_pattern_fast(
-    current_dir: &Path,
-    search_term: &str,
-    matches: &mut Vec<DirectoryMatch>,
-    context: &mut SearchContext,
-    case_sensitive: bool,
-) {
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_path_pattern_fast: current_dir={}, search_term='{}', case_sensitive={}",
-            current_dir.display(),
-            search_term,
-            case_sensitive
-        );
-    }
-
-    let parts: Vec<&str> = search_term.split('/').collect();
-    if parts.is_empty() || !context.should_continue() {
-        if is_debug_enabled() {
-            eprintln!(
-                "DEBUG: search_path_pattern_fast: early exit - parts empty or context expired"
-            );
-        }
-        return;
-    }
-
-    let first_part = parts[0];
-    let remaining_parts = &parts[1..];
-
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_path_pattern_fast: split into first_part='{}', remaining_parts={:?}",
-            first_part, remaining_parts
-        );
-    }
-
-    // Search for the first part in current directory and subdirectories
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_path_pattern_fast: starting recursive search down from current dir"
-        );
-    }
-    search_pattern_recursive_fast(
-        current_dir,
-        first_part,
-        remaining_parts,
-        matches,
-        context,
-        0,
-        4,
-        case_sensitive,
-    );
-
-    // Also search up the tree for the first part (but limit this to avoid slowdown)
-    if is_debug_enabled() {
-        eprintln!("DEBUG: search_path_pattern_fast: starting search up the tree");
-    }
-    let mut current = current_dir;
-    let mut depth = -1;
-    let mut up_count = 0;
-
-    while let Some(parent) = current.parent() {
-        if !context.should_continue() || up_count >= 10 {
-            if is_debug_enabled() {
-                eprintln!("DEBUG: search_path_pattern_fast: stopping up search - context expired or max up count reached");
-            }
-            break;
-        }
-
-        if let Some(name) = parent.file_name() {
-            let name_str = name.to_string_lossy();
-            if is_debug_enabled() {
-                eprintln!(
-                    "DEBUG: search_path_pattern_fast: checking parent '{}' at depth {}",
-                    name_str, depth
-                );
-            }
-
-            let matches_pattern = if case_sensitive {
-                name_str.contains(first_part)
-            } else {
-                name_str.to_lowercase().contains(&first_part.to_lowercase())
-            };
-
-            if matches_pattern {
-                if is_debug_enabled() {
-                    eprintln!(
-                        "DEBUG: search_path_pattern_fast: parent '{}' contains pattern '{}'",
-                        name_str, first_part
-                    );
-                }
-
-                if remaining_parts.is_empty() {
-                    let match_quality = if name_str.to_lowercase() == first_part.to_lowercase() {
-                        MatchQuality::ExactUp
-                    } else {
-                        MatchQuality::PartialUp
-                    };
-
-                    if is_debug_enabled() {
-                        eprintln!(
-                            "DEBUG: search_path_pattern_fast: adding up match {:?} for {}",
-                            match_quality,
-                            parent.display()
-                        );
-                    }
-
-                    matches.push(DirectoryMatch {
-                        path: parent.to_path_buf(),
-                        depth_from_current: depth,
-                        match_quality,
-                    });
-                    context.add_match();
-                } else {
-                    if is_debug_enabled() {
-                        eprintln!("DEBUG: search_path_pattern_fast: recursing from parent for remaining patterns");
-                    }
-                    search_pattern_recursive_fast(
-                        parent,
-                        &remaining_parts[0],
-                        &remaining_parts[1..],
-                        matches,
-                        context,
-                        depth,
-                        3,
-                        case_sensitive,
-                    );
-                }
-            }
-        }
-        current = parent;
-        depth -= 1;
-        up_count += 1;
-    }
-
-    if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_path_pattern_fast: completed with {} total matches",
-            matches.len()
-        );
-    }
-}
-
-fn search_pattern_recursive_fast(
-    current_dir: &Path,
-    pattern: &str,
-    remaining_patterns: &[&str],
-    matches: &mut Vec<DirectoryMatch>,
-    context: &mut SearchContext,
-    base_depth: i32,
-    max_depth: usize,
-    case_sensitive: bool,
-) {
-    if is_debug_enabled() {
-        eprintln!("DEBUG: search_pattern_recursive_fast: dir={}, pattern='{}', remaining={:?}, base_depth={}, max_depth={}, case_sensitive={}",
-                 current_dir.display(), pattern, remaining_patterns, base_depth, max_depth, case_sensitive);
-    }
-
-    if max_depth == 0 || !context.should_continue() {
-        if is_debug_enabled() {
-            eprintln!(
-                "DEBUG: search_pattern_recursive_fast: early exit - max_depth=0 or context expired"
-            );
-        }
-        return;
-    }
-
-    if let Ok(entries) = fs::read_dir(current_dir) {
-        let mut entry_count = 0;
-        let mut match_count = 0;
-
-        for entry in entries.flatten() {
-            entry_count += 1;
-
-            if !context.should_continue() {
-                if is_debug_enabled() {
-                    eprintln!(
-                        "DEBUG: search_pattern_recursive_fast: breaking due to context timeout"
-                    );
-                }
-                break;
-            }
-
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_dir() {
-                    let path = entry.path();
-                    if let Some(name) = path.file_name() {
-                        let name_str = name.to_string_lossy();
-                        let matches_pattern = if case_sensitive {
-                            name_str.contains(pattern)
-                        } else {
-                            name_str.to_lowercase().contains(&pattern.to_lowercase())
-                        };
-
-                        if matches_pattern {
-                            match_count += 1;
-
-                            if is_debug_enabled() {
-                                eprintln!("DEBUG: search_pattern_recursive_fast: found matching dir '{}' for pattern '{}'", name_str, pattern);
-                            }
-
-                            if remaining_patterns.is_empty() {
-                                let is_exact = if case_sensitive {
-                                    name_str == pattern
-                                } else {
-                                    name_str.to_lowercase() == pattern.to_lowercase()
-                                };
-
-                                let match_quality = if is_exact {
-                                    if base_depth < 0 {
-                                        MatchQuality::ExactUp
-                                    } else {
-                                        MatchQuality::ExactDown
-                                    }
-                                } else {
-                                    if base_depth < 0 {
-                                        MatchQuality::PartialUp
-                                    } else {
-                                        MatchQuality::PartialDown
-                                    }
-                                };
-
-                                if is_debug_enabled() {
-                                    eprintln!("DEBUG: search_pattern_recursive_fast: adding final match {:?} for {}", match_quality, path.display());
-                                }
-
-                                matches.push(DirectoryMatch {
-                                    path: path.clone(),
-                                    depth_from_current: base_depth + 1,
-                                    match_quality,
-                                });
-                                context.add_match();
-                            } else {
-                                if is_debug_enabled() {
-                                    eprintln!("DEBUG: search_pattern_recursive_fast: recursing deeper for remaining patterns");
-                                }
-                                search_pattern_recursive_fast(
-                                    &path,
-                                    remaining_patterns[0],
-                                    &remaining_patterns[1..],
-                                    matches,
-                                    context,
-                                    base_depth + 1,
-                                    max_depth - 1,
-                                    case_sensitive,
-                                );
-                            }
-                        }
-
-                        // Also recurse into subdirectories to find pattern deeper
-                        if context.should_continue() {
-                            search_pattern_recursive_fast(
-                                &path,
-                                pattern,
-                                remaining_patterns,
-                                matches,
-                                context,
-                                base_depth + 1,
-                                max_depth - 1,
-                                case_sensitive,
-                            );
-                        }
-                    }
-                }
-            }
-        }
-
-        if is_debug_enabled() {
-            eprintln!("DEBUG: search_pattern_recursive_fast: processed {} entries, found {} pattern matches in {}",
-                     entry_count, match_count, current_dir.display());
-        }
-    } else if is_debug_enabled() {
-        eprintln!(
-            "DEBUG: search_pattern_recursive_fast: failed to read directory {}",
-            current_dir.display()
-        );
-    }
-}
-
-fn search_absolute_pattern(
-    parent_dir: &Path,
-    pattern: &str,
-    matches: &mut Vec<DirectoryMatch>,
-    case_sensitive: bool,
-) {
-    use std::collections::VecDeque;
-
-    let mut queue = VecDeque::new();
-    let mut immediate_matches: Vec<DirectoryMatch> = Vec::new();
-    queue.push_back((parent_dir.to_path_buf(), 0));
-    let search_lower = if case_sensitive {
-        pattern.to_string()
-    } else {
-        pattern.to_lowercase()
-    };
-    let max_depth = 8;
-
-    // First, search immediate subdirectories (depth 1) to check for early stopping
-    if let Ok(entries) = fs::read_dir(parent_dir) {
-        let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-        entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
-
-        for entry in &entries {
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_dir() {
-                    let path = entry.path();
-                    if let Some(name) = path.file_name() {
-                        let name_str = name.to_string_lossy();
-                        let (name_compare, search_compare) = if case_sensitive {
-                            (name_str.to_string(), pattern.to_string())
-                        } else {
-                            (name_str.to_lowercase(), search_lower.clone())
-                        };
-
-                        // Check for immediate matches
-                        if name_compare == search_compare {
-                            let dir_match = DirectoryMatch {
-                                path: path.clone(),
-                                depth_from_current: 1,
-                                match_quality: MatchQuality::ExactDown,
-                            };
-                            immediate_matches.push(dir_match.clone());
-                            matches.push(dir_match);
-                        } else if name_compare.starts_with(&search_compare) {
-                            let dir_match = DirectoryMatch {
-                                path: path.clone(),
-                                depth_from_current: 1,
-                                match_quality: MatchQuality::PrefixDown,
-                            };
-                            immediate_matches.push(dir_match.clone());
-                            matches.push(dir_match);
-                        } else if name_compare.contains(&search_compare) {
-                            let dir_match = DirectoryMatch {
-                                path: path.clone(),
-                                depth_from_current: 1,
-                                match_quality: MatchQuality::PartialDown,
-                            };
-                            immediate_matches.push(dir_match.clone());
-                            matches.push(dir_match);
-                        }
-
-                        // Add subdirectories to queue for potential deeper search
-                        queue.push_back((path.clone(), 1));
-                    }
-                }
-            }
-        }
-    }
-
-    // If there are any matches in immediate subdirectories, return early to avoid deep search
-    // This prioritizes local matches over distant ones (same logic as relative paths)
-    if !immediate_matches.is_empty() {
-        return;
-    }
-
-    // Otherwise, continue with breadth-first search for deeper levels
-    while let Some((current_dir, depth)) = queue.pop_front() {
-        if depth == 0 || depth > max_depth {
-            continue; // Skip depth 0 (already processed) and beyond max depth
-        }
-
-        if let Ok(entries) = fs::read_dir(&current_dir) {
-            let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-            entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
-
-            for entry in &entries {
-                if let Ok(metadata) = entry.metadata() {
-                    if metadata.is_dir() {
-                        let path = entry.path();
-                        if let Some(name) = path.file_name() {
-                            let name_str = name.to_string_lossy();
-                            let (name_compare, search_compare) = if case_sensitive {
-                                (name_str.to_string(), pattern.to_string())
-                            } else {
-                                (name_str.to_lowercase(), search_lower.clone())
-                            };
-
-                            // Check for pattern match at deeper levels
-                            if name_compare == search_compare {
-                                matches.push(DirectoryMatch {
-                                    path: path.clone(),
-                                    depth_from_current: depth as i32,
-                                    match_quality: MatchQuality::ExactDown,
-                                });
-                            } else if name_compare.starts_with(&search_compare) {
-                                matches.push(DirectoryMatch {
-                                    path: path.clone(),
-                                    depth_from_current: depth as i32,
-                                    match_quality: MatchQuality::PrefixDown,
-                                });
-                            } else if name_compare.contains(&search_compare) {
-                                matches.push(DirectoryMatch {
-                                    path: path.clone(),
-                                    depth_from_current: depth as i32,
-                                    match_quality: MatchQuality::PartialDown,
-                                });
-                            }
-
-                            // Add subdirectories to queue for next level search
-                            if depth < max_depth {
-                                queue.push_back((path, depth + 1));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
-fn find_search_root_and_pattern(search_term: &str) -> (Option<PathBuf>, String) {
-    let path = Path::new(search_term);
-    let mut current = path;
-
-    // Walk up the path to find the longest existing prefix
-    while let Some(parent) = current.parent() {
-        if parent.exists() && parent.is_dir() {
-            // Found existing parent directory
-            // The search pattern is the first component after this parent
-            let remaining = path.strip_prefix(parent).unwrap();
-            let mut components = remaining.components();
-            if let Some(first_component) = components.next() {
-                let pattern = first_component.as_os_str().to_string_lossy().to_string();
-                return (Some(parent.to_path_buf()), pattern);
-            }
-        }
-        current = parent;
-    }
-
-    // If we get here, even root doesn't exist (shouldn't happen on Unix)
-    // Fall back to searching from root with the first component as pattern
-    let first_component = Path::new(search_term)
-        .components()
-        .nth(1) // Skip the root component "/"
-        .map(|c| c.as_os_str().to_string_lossy().to_string())
-        .unwrap_or_else(|| search_term.trim_start_matches('/').to_string());
-    (Some(PathBuf::from("/")), first_component)
-}
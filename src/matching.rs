@@ -0,0 +1,550 @@
+// Name matching. Each directory name is compared with one term of the query: exactly, as a
+// prefix, by word initials (-w), as a substring or as a scored subsequence (-f) in literal
+// mode, or through a regex (-r) or glob (-g). The strongest kind of match decides the tier
+// the directory is ranked in, and the matched byte ranges are kept for highlighting. Search
+// strategies only see the `Matcher` trait, so a new kind of match is added here once and
+// behaves the same up the tree, down it and along path patterns.
+
+use std::{borrow::Cow, fmt};
+
+use crate::{
+    acronym, fuzzy, normalize,
+    normalize::Normalization,
+    pattern::{CompiledPatterns, PatternMode},
+    typo, MatchQuality,
+};
+
+/// How letter case is compared, chosen by flag or the `case` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CasePolicy {
+    Sensitive,   // Case must match exactly (-S)
+    Insensitive, // Case is ignored (-i)
+    Smart,       // Case is ignored unless the pattern has an uppercase letter (-s, the default)
+}
+
+impl CasePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "sensitive" => Some(CasePolicy::Sensitive),
+            "insensitive" => Some(CasePolicy::Insensitive),
+            "smart" => Some(CasePolicy::Smart),
+            _ => None,
+        }
+    }
+
+    /// Whether comparisons against `pattern` are case-sensitive under this policy.
+    /// Escaped characters in regex patterns such as `\W` or `\D` are not letters of the name.
+    pub fn is_case_sensitive(self, pattern: &str, mode: PatternMode) -> bool {
+        match self {
+            CasePolicy::Sensitive => true,
+            CasePolicy::Insensitive => false,
+            CasePolicy::Smart => {
+                let mut escaped = false;
+                pattern.chars().any(|c| {
+                    let literal = !escaped;
+                    escaped = mode == PatternMode::Regex && !escaped && c == '\\';
+                    literal && c.is_uppercase()
+                })
+            }
+        }
+    }
+}
+
+/// Options controlling how directory names are compared with the pattern
+#[derive(Debug, Clone)]
+pub struct MatchOptions {
+    pub case_sensitive: bool,
+    pub fuzzy: bool,                  // fall back to scored subsequence matching (-f)
+    pub acronym: bool,                // match pattern characters against word initials (-w)
+    pub mode: PatternMode,            // literal, regex (-r) or glob (-g) patterns
+    pub normalization: Normalization, // Unicode normalization applied before comparing (-u, -a)
+    pub compiled: CompiledPatterns,   // the query's regex and glob terms, compiled up front
+}
+
+/// How a single directory name matched the pattern, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameMatchKind {
+    Exact,
+    Prefix,
+    Acronym,
+    Partial,
+    Fuzzy,
+    Typo,
+}
+
+impl NameMatchKind {
+    /// Ranking tier for this kind of name match found up or down the tree.
+    /// There is no prefix tier up the tree, so parent prefixes count as partial matches.
+    pub fn quality(self, up: bool) -> MatchQuality {
+        match (self, up) {
+            (NameMatchKind::Exact, true) => MatchQuality::ExactUp,
+            (NameMatchKind::Exact, false) => MatchQuality::ExactDown,
+            (NameMatchKind::Prefix | NameMatchKind::Partial, true) => MatchQuality::PartialUp,
+            (NameMatchKind::Prefix, false) => MatchQuality::PrefixDown,
+            (NameMatchKind::Acronym, true) => MatchQuality::AcronymUp,
+            (NameMatchKind::Acronym, false) => MatchQuality::AcronymDown,
+            (NameMatchKind::Partial, false) => MatchQuality::PartialDown,
+            (NameMatchKind::Fuzzy, _) => MatchQuality::Fuzzy,
+            (NameMatchKind::Typo, _) => MatchQuality::Typo,
+        }
+    }
+}
+
+/// Compares directory names with one term of the query for every search strategy; set
+/// `SearchOptions::matcher` to replace the built-in comparison
+pub trait Matcher: fmt::Debug + Send + Sync {
+    /// How `name` matches `pattern`, if it does
+    fn match_name(&self, name: &str, pattern: &str) -> Option<NameMatch>;
+
+    /// Looser comparison tried only when a search found nothing; none by default
+    fn match_typo(&self, _name: &str, _pattern: &str) -> Option<NameMatch> {
+        None
+    }
+}
+
+/// The built-in matcher: the mode, case and normalization the query was given
+impl Matcher for MatchOptions {
+    fn match_name(&self, name: &str, pattern: &str) -> Option<NameMatch> {
+        match_name(name, pattern, self)
+    }
+
+    fn match_typo(&self, name: &str, pattern: &str) -> Option<NameMatch> {
+        match_name_typo(name, pattern, self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NameMatch {
+    pub kind: NameMatchKind,
+    pub spans: Vec<(usize, usize)>, // byte ranges of the name that matched
+    pub score: i32, // fuzzy alignment score, minus the edits of a typo match, or 1 for a case-exact match when case is ignored
+}
+
+/// Lowercase `name`, remembering for each output byte the byte offset of the source character
+fn fold_case_with_offsets(name: &str) -> (String, Vec<usize>) {
+    let mut folded = String::with_capacity(name.len());
+    let mut offsets = Vec::with_capacity(name.len());
+    for (offset, ch) in name.char_indices() {
+        for lower in ch.to_lowercase() {
+            let before = folded.len();
+            folded.push(lower);
+            offsets.resize(offsets.len() + folded.len() - before, offset);
+        }
+    }
+    (folded, offsets)
+}
+
+/// Byte ranges of `name` covered by the first substring match of `pattern`
+fn match_spans(name: &str, pattern: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+
+    if case_sensitive {
+        return name
+            .find(pattern)
+            .map(|start| vec![(start, start + pattern.len())])
+            .unwrap_or_default();
+    }
+
+    // Match on the lowercased name, then map the range back onto the original bytes
+    let (folded, offsets) = fold_case_with_offsets(name);
+    let pattern = pattern.to_lowercase();
+    match folded.find(&pattern) {
+        Some(start) => {
+            let last = offsets[start + pattern.len() - 1];
+            let end = last + name[last..].chars().next().map_or(0, char::len_utf8);
+            vec![(offsets[start], end)]
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Compare one directory name with the pattern: exact, prefix, acronym if enabled, substring,
+/// then fuzzy if enabled
+fn match_name(name: &str, pattern: &str, options: &MatchOptions) -> Option<NameMatch> {
+    if options.normalization != Normalization::None {
+        return match_name_normalized(name, pattern, options);
+    }
+
+    match options.mode {
+        PatternMode::Regex => return match_name_regex(name, pattern, options),
+        PatternMode::Glob => return match_name_glob(name, pattern, options),
+        PatternMode::Literal => {}
+    }
+
+    let (name_compare, pattern_compare) = if options.case_sensitive {
+        (Cow::Borrowed(name), Cow::Borrowed(pattern))
+    } else {
+        (
+            Cow::Owned(name.to_lowercase()),
+            Cow::Owned(pattern.to_lowercase()),
+        )
+    };
+
+    let kind = if name_compare == pattern_compare {
+        Some(NameMatchKind::Exact)
+    } else if name_compare.starts_with(pattern_compare.as_ref()) {
+        Some(NameMatchKind::Prefix)
+    } else if name_compare.contains(pattern_compare.as_ref()) {
+        Some(NameMatchKind::Partial)
+    } else {
+        None
+    };
+
+    // Acronyms rank above substring matches, so they are tried before settling for one
+    if options.acronym && !matches!(kind, Some(NameMatchKind::Exact | NameMatchKind::Prefix)) {
+        if let Some(positions) = acronym::acronym_match(name, pattern, options.case_sensitive) {
+            return Some(NameMatch {
+                kind: NameMatchKind::Acronym,
+                spans: char_spans(name, &positions),
+                score: 0,
+            });
+        }
+    }
+
+    if let Some(kind) = kind {
+        let spans = match_spans(name, pattern, options.case_sensitive);
+        let case_exact = !options.case_sensitive && is_spelled_like(name, &spans, pattern);
+        return Some(NameMatch {
+            kind,
+            spans,
+            score: i32::from(case_exact),
+        });
+    }
+
+    if !options.fuzzy {
+        return None;
+    }
+    let (score, positions) = fuzzy::fuzzy_match(name, pattern, options.case_sensitive)?;
+    Some(NameMatch {
+        kind: NameMatchKind::Fuzzy,
+        spans: char_spans(name, &positions),
+        score,
+    })
+}
+
+/// When case is ignored, names spelled exactly like the pattern win ties: `testdir` ranks
+/// testdir ahead of TestDir
+fn is_spelled_like(name: &str, spans: &[(usize, usize)], pattern: &str) -> bool {
+    spans
+        .first()
+        .is_some_and(|&(start, end)| &name[start..end] == pattern)
+}
+
+/// Compare the normalized name and pattern, then report spans against the original name.
+/// Literal comparisons fold case here; regex and glob patterns handle case themselves.
+fn match_name_normalized(name: &str, pattern: &str, options: &MatchOptions) -> Option<NameMatch> {
+    let fold_case = !options.case_sensitive && options.mode == PatternMode::Literal;
+    let (normalized_name, offsets) =
+        normalize::normalize_with_offsets(name, options.normalization, fold_case);
+    let normalized_pattern = normalize::normalize(pattern, options.normalization, fold_case);
+
+    let normalized_options = MatchOptions {
+        case_sensitive: options.case_sensitive || fold_case,
+        normalization: Normalization::None,
+        ..options.clone()
+    };
+    let mut name_match = match_name(&normalized_name, &normalized_pattern, &normalized_options)?;
+    name_match.spans = normalize::original_spans(name, &offsets, &name_match.spans);
+    if fold_case && name_match.kind != NameMatchKind::Fuzzy {
+        name_match.score = i32::from(is_spelled_like(name, &name_match.spans, pattern));
+    }
+    Some(name_match)
+}
+
+/// Regex mode: the first non-empty match decides the quality, so `^api` still ranks as a prefix
+fn match_name_regex(name: &str, pattern: &str, options: &MatchOptions) -> Option<NameMatch> {
+    let regex = options
+        .compiled
+        .get(pattern, PatternMode::Regex, options.case_sensitive)?;
+    let found = regex.find_iter(name).find(|m| !m.is_empty())?;
+
+    let kind = if found.start() == 0 && found.end() == name.len() {
+        NameMatchKind::Exact
+    } else if found.start() == 0 {
+        NameMatchKind::Prefix
+    } else {
+        NameMatchKind::Partial
+    };
+    Some(NameMatch {
+        kind,
+        spans: vec![(found.start(), found.end())],
+        score: 0,
+    })
+}
+
+/// Glob mode: globs match the whole name, and the wildcards at either end decide the quality.
+/// `svc-*-api` pins both ends (exact), `svc-*` only the start (prefix), `*-api` neither (partial).
+fn match_name_glob(name: &str, pattern: &str, options: &MatchOptions) -> Option<NameMatch> {
+    let regex = options
+        .compiled
+        .get(pattern, PatternMode::Glob, options.case_sensitive)?;
+    let captures = regex.captures(name)?;
+
+    let kind = if pattern.starts_with('*') {
+        NameMatchKind::Partial
+    } else if pattern.ends_with('*') {
+        NameMatchKind::Prefix
+    } else {
+        NameMatchKind::Exact
+    };
+    // Each literal run of the glob is a capture group
+    let spans = captures
+        .iter()
+        .skip(1)
+        .flatten()
+        .filter(|m| !m.is_empty())
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    Some(NameMatch {
+        kind,
+        spans,
+        score: 0,
+    })
+}
+
+/// Last-resort comparison for searches that found nothing: names a few typos away from a
+/// literal pattern. Closer names score higher; there are no spans, as edits do not align.
+fn match_name_typo(name: &str, pattern: &str, options: &MatchOptions) -> Option<NameMatch> {
+    if options.mode != PatternMode::Literal {
+        return None;
+    }
+    let limit = typo::max_edits(pattern.chars().count());
+    if limit == 0 || pattern.contains('/') {
+        return None;
+    }
+
+    let fold_case = !options.case_sensitive;
+    let (name, pattern) = if options.normalization != Normalization::None {
+        (
+            Cow::Owned(normalize::normalize(name, options.normalization, fold_case)),
+            Cow::Owned(normalize::normalize(
+                pattern,
+                options.normalization,
+                fold_case,
+            )),
+        )
+    } else if fold_case {
+        (
+            Cow::Owned(name.to_lowercase()),
+            Cow::Owned(pattern.to_lowercase()),
+        )
+    } else {
+        (Cow::Borrowed(name), Cow::Borrowed(pattern))
+    };
+
+    let edits = typo::distance(&name, &pattern, limit)?;
+    Some(NameMatch {
+        kind: NameMatchKind::Typo,
+        spans: Vec::new(),
+        score: -(edits as i32),
+    })
+}
+
+/// Merge the byte offsets of individual matched characters into contiguous byte ranges
+fn char_spans(name: &str, positions: &[usize]) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    for &start in positions {
+        let end = start + name[start..].chars().next().map_or(0, char::len_utf8);
+        match spans.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => spans.push((start, end)),
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal() -> MatchOptions {
+        MatchOptions {
+            case_sensitive: false,
+            fuzzy: false,
+            acronym: false,
+            mode: PatternMode::Literal,
+            normalization: Normalization::None,
+            compiled: CompiledPatterns::default(),
+        }
+    }
+
+    fn kind(name: &str, pattern: &str, options: &MatchOptions) -> Option<NameMatchKind> {
+        match_name(name, pattern, options).map(|m| m.kind)
+    }
+
+    #[test]
+    fn literal_tiers_from_exact_to_partial() {
+        let options = literal();
+        assert_eq!(
+            kind("service", "service", &options),
+            Some(NameMatchKind::Exact)
+        );
+        assert_eq!(
+            kind("service", "serv", &options),
+            Some(NameMatchKind::Prefix)
+        );
+        assert_eq!(
+            kind("service", "vic", &options),
+            Some(NameMatchKind::Partial)
+        );
+        assert_eq!(kind("service", "svc", &options), None);
+    }
+
+    #[test]
+    fn fuzzy_matches_only_when_enabled_and_nothing_stronger_does() {
+        let options = MatchOptions {
+            fuzzy: true,
+            ..literal()
+        };
+        assert_eq!(kind("service", "svc", &options), Some(NameMatchKind::Fuzzy));
+        assert_eq!(
+            kind("service", "serv", &options),
+            Some(NameMatchKind::Prefix)
+        );
+        assert_eq!(kind("service", "xyz", &options), None);
+    }
+
+    #[test]
+    fn acronyms_rank_between_prefix_and_partial() {
+        let options = MatchOptions {
+            acronym: true,
+            ..literal()
+        };
+        let found = match_name("DataBaseUtils", "dbu", &options).unwrap();
+        assert_eq!(found.kind, NameMatchKind::Acronym);
+        assert_eq!(found.spans, [(0, 1), (4, 5), (8, 9)]);
+        // A prefix is stronger than the initials it also spells
+        assert_eq!(kind("fb-bar", "fb", &options), Some(NameMatchKind::Prefix));
+        // Acronyms are off unless asked for
+        assert_eq!(kind("DataBaseUtils", "dbu", &literal()), None);
+    }
+
+    #[test]
+    fn case_is_folded_unless_sensitive() {
+        let sensitive = MatchOptions {
+            case_sensitive: true,
+            ..literal()
+        };
+        assert_eq!(kind("Service", "serv", &sensitive), None);
+        assert_eq!(
+            kind("Service", "Serv", &sensitive),
+            Some(NameMatchKind::Prefix)
+        );
+
+        let found = match_name("MyService", "SERV", &literal()).unwrap();
+        assert_eq!(found.kind, NameMatchKind::Partial);
+        assert_eq!(found.spans, [(2, 6)]);
+        assert_eq!(found.score, 0);
+        // Names spelled exactly like the pattern score a tie-break point
+        assert_eq!(
+            match_name("MyService", "Serv", &literal()).unwrap().score,
+            1
+        );
+    }
+
+    #[test]
+    fn smart_case_looks_for_uppercase_letters() {
+        let smart = CasePolicy::Smart;
+        assert!(!smart.is_case_sensitive("service", PatternMode::Literal));
+        assert!(smart.is_case_sensitive("Service", PatternMode::Literal));
+        assert!(!smart.is_case_sensitive(r"\W\D", PatternMode::Regex));
+        assert!(smart.is_case_sensitive(r"\WA", PatternMode::Regex));
+        assert!(smart.is_case_sensitive(r"\W", PatternMode::Literal));
+        assert!(CasePolicy::Sensitive.is_case_sensitive("x", PatternMode::Literal));
+        assert!(!CasePolicy::Insensitive.is_case_sensitive("X", PatternMode::Literal));
+        assert_eq!(CasePolicy::parse("smart"), Some(CasePolicy::Smart));
+        assert_eq!(CasePolicy::parse("Smart"), None);
+    }
+
+    #[test]
+    fn regex_and_glob_anchors_decide_the_tier() {
+        let regex = MatchOptions {
+            mode: PatternMode::Regex,
+            ..literal()
+        };
+        assert_eq!(
+            kind("api-gateway", "^api", &regex),
+            Some(NameMatchKind::Prefix)
+        );
+        assert_eq!(
+            kind("api-gateway", "^api.*y$", &regex),
+            Some(NameMatchKind::Exact)
+        );
+        assert_eq!(
+            kind("api-gateway", "gate", &regex),
+            Some(NameMatchKind::Partial)
+        );
+
+        let glob = MatchOptions {
+            mode: PatternMode::Glob,
+            ..literal()
+        };
+        assert_eq!(
+            kind("svc-user-api", "svc-*-api", &glob),
+            Some(NameMatchKind::Exact)
+        );
+        assert_eq!(
+            kind("svc-user-api", "svc-*", &glob),
+            Some(NameMatchKind::Prefix)
+        );
+        assert_eq!(
+            kind("svc-user-api", "*-api", &glob),
+            Some(NameMatchKind::Partial)
+        );
+        assert_eq!(kind("svc-user-api", "svc", &glob), None);
+    }
+
+    #[test]
+    fn typos_are_literal_and_need_a_long_enough_pattern() {
+        let found = match_name_typo("service", "servcie", &literal()).unwrap();
+        assert_eq!(found.kind, NameMatchKind::Typo);
+        assert_eq!(found.score, -1);
+        assert!(match_name_typo("src", "scr", &literal()).is_none());
+        assert!(match_name_typo("service", "xyzxyzx", &literal()).is_none());
+
+        let regex = MatchOptions {
+            mode: PatternMode::Regex,
+            ..literal()
+        };
+        assert!(match_name_typo("service", "servcie", &regex).is_none());
+    }
+
+    #[test]
+    fn qualities_depend_on_direction() {
+        assert_eq!(NameMatchKind::Exact.quality(true), MatchQuality::ExactUp);
+        assert_eq!(NameMatchKind::Exact.quality(false), MatchQuality::ExactDown);
+        assert_eq!(NameMatchKind::Prefix.quality(true), MatchQuality::PartialUp);
+        assert_eq!(
+            NameMatchKind::Prefix.quality(false),
+            MatchQuality::PrefixDown
+        );
+        assert_eq!(
+            NameMatchKind::Acronym.quality(true),
+            MatchQuality::AcronymUp
+        );
+        assert_eq!(NameMatchKind::Fuzzy.quality(true), MatchQuality::Fuzzy);
+        assert_eq!(NameMatchKind::Typo.quality(false), MatchQuality::Typo);
+    }
+
+    #[test]
+    fn query_patterns_are_compiled_once_up_front() {
+        assert!(CompiledPatterns::new(["^api", "^api", "gate"], PatternMode::Regex, false).is_ok());
+        let failed = CompiledPatterns::new(["^api", "(oops"], PatternMode::Regex, false);
+        assert_eq!(failed.unwrap_err().0, "(oops");
+
+        let regex = MatchOptions {
+            mode: PatternMode::Regex,
+            compiled: CompiledPatterns::new(["^API"], PatternMode::Regex, true).unwrap(),
+            ..literal()
+        };
+        // A pattern of the query uses the regex compiled with it
+        assert_eq!(kind("api-gateway", "^API", &regex), None);
+        // Patterns the query did not include are still compiled when asked for
+        assert_eq!(
+            kind("api-gateway", "^api", &regex),
+            Some(NameMatchKind::Prefix)
+        );
+    }
+}
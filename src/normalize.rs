@@ -0,0 +1,124 @@
+// Optional Unicode layer in front of the matcher. Names and patterns are brought to
+// canonical decomposition (NFD) so NFC and NFD spellings compare equal, case is removed
+// with full Unicode case folding (`Straße` matches `strasse`), and diacritics can be
+// dropped so `Café` matches `cafe`. Every normalized byte remembers the original
+// character it came from, so match spans are still reported against the original name.
+
+use caseless::Caseless;
+use unicode_normalization::char::{canonical_combining_class, decompose_canonical};
+
+/// Which Unicode normalization is applied before names are compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    None,          // Names are compared as stored (the default)
+    Unicode,       // Canonical decomposition and full case folding (-u)
+    IgnoreAccents, // As Unicode, with combining marks removed (-a)
+}
+
+impl Normalization {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(Normalization::None),
+            "unicode" => Some(Normalization::Unicode),
+            "ignore-accents" => Some(Normalization::IgnoreAccents),
+            _ => None,
+        }
+    }
+}
+
+/// Canonical decomposition of a single character
+fn decompose(c: char, out: &mut Vec<char>) {
+    decompose_canonical(c, |d| out.push(d));
+}
+
+/// Normalize `text`, returning the result and, for each of its bytes, the byte offset
+/// in `text` of the character it came from. Case is folded only when `fold_case` is set.
+pub fn normalize_with_offsets(
+    text: &str,
+    normalization: Normalization,
+    fold_case: bool,
+) -> (String, Vec<usize>) {
+    if normalization == Normalization::None || text.is_ascii() {
+        let folded = if fold_case && normalization != Normalization::None {
+            text.to_ascii_lowercase()
+        } else {
+            text.to_string()
+        };
+        return (folded, (0..text.len()).collect());
+    }
+
+    // Decompose, fold and decompose again: case folding can produce precomposed characters
+    let mut chars: Vec<(char, usize)> = Vec::with_capacity(text.len());
+    let mut decomposed = Vec::new();
+    let mut folded = Vec::new();
+    for (offset, c) in text.char_indices() {
+        decomposed.clear();
+        decompose(c, &mut decomposed);
+        if fold_case {
+            folded.clear();
+            for f in decomposed.iter().copied().default_case_fold() {
+                decompose(f, &mut folded);
+            }
+            std::mem::swap(&mut decomposed, &mut folded);
+        }
+        chars.extend(decomposed.iter().map(|&d| (d, offset)));
+    }
+
+    // Canonical ordering: runs of combining marks are sorted by combining class
+    let mut start = 0;
+    while start < chars.len() {
+        if canonical_combining_class(chars[start].0) == 0 {
+            start += 1;
+            continue;
+        }
+        let mut end = start;
+        while end < chars.len() && canonical_combining_class(chars[end].0) != 0 {
+            end += 1;
+        }
+        chars[start..end].sort_by_key(|&(c, _)| canonical_combining_class(c));
+        start = end;
+    }
+
+    if normalization == Normalization::IgnoreAccents {
+        chars.retain(|&(c, _)| canonical_combining_class(c) == 0);
+    }
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len());
+    for (c, offset) in chars {
+        normalized.push(c);
+        offsets.resize(normalized.len(), offset);
+    }
+    (normalized, offsets)
+}
+
+/// Normalize `text` without keeping the offsets, for patterns
+pub fn normalize(text: &str, normalization: Normalization, fold_case: bool) -> String {
+    normalize_with_offsets(text, normalization, fold_case).0
+}
+
+/// Map byte ranges of a normalized name back onto the original name, merging ranges
+/// that land on the same or adjacent original characters
+pub fn original_spans(
+    original: &str,
+    offsets: &[usize],
+    spans: &[(usize, usize)],
+) -> Vec<(usize, usize)> {
+    let mut mapped: Vec<(usize, usize)> = Vec::new();
+    for &(start, end) in spans {
+        if start >= end || end > offsets.len() {
+            continue;
+        }
+        let first = offsets[start];
+        let last = offsets[end - 1];
+        let span = (
+            first,
+            last + original[last..].chars().next().map_or(0, char::len_utf8),
+        );
+        match mapped.last_mut() {
+            Some(previous) if previous.1 >= span.0 => previous.1 = previous.1.max(span.1),
+            _ => mapped.push(span),
+        }
+    }
+    mapped
+}
@@ -0,0 +1,140 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::MAX_COMPILED_REGEX_SIZE;
+
+/// How the search term is interpreted when comparing directory names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatternMode {
+    Literal, // Exact, prefix and substring comparisons (the default)
+    Regex,   // Regular expression matched anywhere in the name (-r)
+    Glob,    // Shell glob matched against the whole name (-g, or auto-detected)
+}
+
+/// Search terms containing glob metacharacters switch to glob mode automatically
+pub fn looks_like_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Translate a shell glob into an anchored regex, capturing each run of literal characters
+/// so the literal parts can be reported as match spans
+fn glob_to_regex(glob: &str) -> String {
+    let mut source = String::from("^");
+    let mut literal = String::new();
+    let chars: Vec<char> = glob.chars().collect();
+
+    let flush = |source: &mut String, literal: &mut String| {
+        if !literal.is_empty() {
+            source.push('(');
+            source.push_str(&regex::escape(literal));
+            source.push(')');
+            literal.clear();
+        }
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => {
+                flush(&mut source, &mut literal);
+                source.push_str(".*");
+            }
+            '?' => {
+                flush(&mut source, &mut literal);
+                source.push('.');
+            }
+            '[' => {
+                // A bracket expression runs to the next ']', which may itself be the first member
+                let start = i + 1;
+                let mut end = start;
+                if chars.get(end) == Some(&'!') || chars.get(end) == Some(&'^') {
+                    end += 1;
+                }
+                if chars.get(end) == Some(&']') {
+                    end += 1;
+                }
+                while end < chars.len() && chars[end] != ']' {
+                    end += 1;
+                }
+                if end >= chars.len() {
+                    literal.push('[');
+                } else {
+                    flush(&mut source, &mut literal);
+                    source.push('[');
+                    let mut members = &chars[start..end];
+                    if let Some('!' | '^') = members.first() {
+                        source.push('^');
+                        members = &members[1..];
+                    }
+                    for &c in members {
+                        if matches!(c, '\\' | '[' | ']' | '&' | '~') {
+                            source.push('\\');
+                        }
+                        source.push(c);
+                    }
+                    source.push(']');
+                    i = end;
+                }
+            }
+            c => literal.push(c),
+        }
+        i += 1;
+    }
+    flush(&mut source, &mut literal);
+    source.push('$');
+    source
+}
+
+/// Compile a search term with the same size limit used for ignore patterns
+pub fn compile(
+    pattern: &str,
+    mode: PatternMode,
+    case_sensitive: bool,
+) -> Result<Regex, regex::Error> {
+    let source = match mode {
+        PatternMode::Glob => glob_to_regex(pattern),
+        PatternMode::Regex | PatternMode::Literal => pattern.to_string(),
+    };
+    RegexBuilder::new(&source)
+        .size_limit(MAX_COMPILED_REGEX_SIZE)
+        .case_insensitive(!case_sensitive)
+        .build()
+}
+
+/// The regexes of one query's regex or glob terms, compiled once when the query is set up and
+/// shared by every name comparison of its search
+#[derive(Debug, Clone, Default)]
+pub struct CompiledPatterns(Arc<HashMap<String, Regex>>);
+
+impl CompiledPatterns {
+    /// Compile each of `patterns`; the first one that does not compile is returned with its error
+    pub fn new<'a>(
+        patterns: impl IntoIterator<Item = &'a str>,
+        mode: PatternMode,
+        case_sensitive: bool,
+    ) -> Result<Self, (String, regex::Error)> {
+        let mut compiled = HashMap::new();
+        for pattern in patterns {
+            if !compiled.contains_key(pattern) {
+                let regex =
+                    compile(pattern, mode, case_sensitive).map_err(|e| (pattern.to_string(), e))?;
+                compiled.insert(pattern.to_string(), regex);
+            }
+        }
+        Ok(CompiledPatterns(Arc::new(compiled)))
+    }
+
+    /// The regex for `pattern`, compiled on the spot if the query did not include it
+    pub fn get(
+        &self,
+        pattern: &str,
+        mode: PatternMode,
+        case_sensitive: bool,
+    ) -> Option<Cow<'_, Regex>> {
+        match self.0.get(pattern) {
+            Some(regex) => Some(Cow::Borrowed(regex)),
+            None => compile(pattern, mode, case_sensitive).ok().map(Cow::Owned),
+        }
+    }
+}
@@ -0,0 +1,400 @@
+// Ranking model behind `finalize_matches`. Each match starts in a bucket picked by its match
+// quality and direction, optionally pushed down for depth, path length and hidden directories,
+// and lifted a few buckets by its visit history. Tie-breakers then order matches within a
+// bucket. The defaults reproduce the built-in ordering; every weight can be changed with
+// `rank.*` keys in the config file. Searches sort through the `Ranker` trait, which `Ranking`
+// implements.
+
+use std::{cmp::Ordering, fmt, path::Component};
+
+use crate::{DirectoryMatch, MatchQuality};
+
+const MAX_FRECENCY_BOOST: u32 = 7; // Frequently visited directories climb at most this many steps
+
+/// Keys that order matches sharing a bucket, applied in turn until one differs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    Frecency, // More visited directories first
+    Priority, // Lower bucket before visit history lifted it
+    Quality,  // Stronger match quality, when several share a bucket
+    Spread,   // Multi-term matches spanning fewer components
+    Score,    // Better fuzzy alignments, closer typos and case-exact spellings
+    Depth,    // Closer to the current directory
+}
+
+impl TieBreak {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "frecency" => Some(TieBreak::Frecency),
+            "priority" => Some(TieBreak::Priority),
+            "quality" => Some(TieBreak::Quality),
+            "spread" => Some(TieBreak::Spread),
+            "score" => Some(TieBreak::Score),
+            "depth" => Some(TieBreak::Depth),
+            _ => None,
+        }
+    }
+}
+
+const DEFAULT_TIE_BREAKS: [TieBreak; 6] = [
+    TieBreak::Frecency,
+    TieBreak::Priority,
+    TieBreak::Quality,
+    TieBreak::Spread,
+    TieBreak::Score,
+    TieBreak::Depth,
+];
+
+/// Orders the matches of a search, best first; every search strategy sorts with one. Set
+/// `SearchOptions::ranker` to replace the `rank.*` ordering.
+pub trait Ranker: fmt::Debug + Send + Sync {
+    fn compare(&self, a: &DirectoryMatch, b: &DirectoryMatch) -> Ordering;
+}
+
+#[derive(Debug, Clone)]
+pub struct Ranking {
+    // Starting bucket by quality and direction; children are at depth 1, deeper is anything below.
+    // The default tiers are two buckets apart, with acronym matches between prefix and partial.
+    pub exact_child: u32,
+    pub prefix_child: u32,
+    pub acronym_child: u32,
+    pub partial_child: u32,
+    pub exact_parent: u32,
+    pub acronym_parent: u32,
+    pub partial_parent: u32, // also prefix matches up the tree
+    pub exact_deeper: u32,
+    pub prefix_deeper: u32,
+    pub acronym_deeper: u32,
+    pub partial_deeper: u32,
+    pub fuzzy: u32,
+    pub history: u32,
+    pub typo: u32,
+    // Penalties in buckets
+    pub depth: u32,       // per level away from the current directory
+    pub path_length: u32, // per component of the full path
+    pub hidden: u32,      // when any component of the path starts with a dot
+    // Buckets climbed per step of visit history; zero turns the lift off
+    pub recency: u32,
+    pub tie_breaks: Vec<TieBreak>,
+}
+
+impl Default for Ranking {
+    fn default() -> Self {
+        Ranking {
+            exact_child: 0,
+            prefix_child: 2,
+            acronym_child: 3,
+            partial_child: 4,
+            exact_parent: 6,
+            acronym_parent: 7,
+            partial_parent: 8,
+            exact_deeper: 10,
+            prefix_deeper: 12,
+            acronym_deeper: 13,
+            partial_deeper: 14,
+            fuzzy: 16,
+            history: 18,
+            typo: 20,
+            depth: 0,
+            path_length: 0,
+            hidden: 0,
+            recency: 2,
+            tie_breaks: DEFAULT_TIE_BREAKS.to_vec(),
+        }
+    }
+}
+
+impl Ranking {
+    /// Apply one `rank.<name> = value` config entry; returns false if it is not understood
+    pub fn set(&mut self, name: &str, value: &str) -> bool {
+        if name == "tiebreak" {
+            let Some(mut keys) = value
+                .split([',', ' '])
+                .filter(|key| !key.is_empty())
+                .map(TieBreak::parse)
+                .collect::<Option<Vec<_>>>()
+            else {
+                return false;
+            };
+            // Keys left out still apply, after the listed ones
+            for key in DEFAULT_TIE_BREAKS {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+            self.tie_breaks = keys;
+            return true;
+        }
+
+        let Ok(value) = value.parse::<u32>() else {
+            return false;
+        };
+        let weight = match name {
+            "exact-child" => &mut self.exact_child,
+            "prefix-child" => &mut self.prefix_child,
+            "acronym-child" => &mut self.acronym_child,
+            "partial-child" => &mut self.partial_child,
+            "exact-parent" => &mut self.exact_parent,
+            "acronym-parent" => &mut self.acronym_parent,
+            "partial-parent" => &mut self.partial_parent,
+            "exact-deeper" => &mut self.exact_deeper,
+            "prefix-deeper" => &mut self.prefix_deeper,
+            "acronym-deeper" => &mut self.acronym_deeper,
+            "partial-deeper" => &mut self.partial_deeper,
+            "fuzzy" => &mut self.fuzzy,
+            "history" => &mut self.history,
+            "typo" => &mut self.typo,
+            "depth" => &mut self.depth,
+            "path-length" => &mut self.path_length,
+            "hidden" => &mut self.hidden,
+            "recency" => &mut self.recency,
+            _ => return false,
+        };
+        *weight = value;
+        true
+    }
+
+    /// Starting bucket for a match quality found at `depth`
+    fn bucket(&self, depth: i32, quality: &MatchQuality) -> u32 {
+        match (depth, quality) {
+            (1, MatchQuality::ExactDown) => self.exact_child,
+            (1, MatchQuality::PrefixDown) => self.prefix_child,
+            (1, MatchQuality::AcronymDown) => self.acronym_child,
+            (1, MatchQuality::PartialDown) => self.partial_child,
+            (_, MatchQuality::ExactUp) => self.exact_parent,
+            (_, MatchQuality::AcronymUp) => self.acronym_parent,
+            (_, MatchQuality::PartialUp) => self.partial_parent,
+            (_, MatchQuality::ExactDown) => self.exact_deeper,
+            (_, MatchQuality::PrefixDown) => self.prefix_deeper,
+            (_, MatchQuality::AcronymDown) => self.acronym_deeper,
+            (_, MatchQuality::PartialDown) => self.partial_deeper,
+            (_, MatchQuality::Fuzzy) => self.fuzzy,
+            (_, MatchQuality::History) => self.history,
+            (_, MatchQuality::Typo) => self.typo,
+        }
+    }
+
+    /// Bucket of a match before its visit history is taken into account
+    pub fn priority(&self, m: &DirectoryMatch) -> u32 {
+        let mut priority = self.bucket(m.depth_from_current, &m.match_quality);
+        if self.depth > 0 {
+            priority += self.depth * m.depth_from_current.unsigned_abs();
+        }
+        if self.path_length > 0 || self.hidden > 0 {
+            let names = m.path.components().filter_map(|c| match c {
+                Component::Normal(name) => Some(name),
+                _ => None,
+            });
+            let (mut length, mut hidden) = (0, false);
+            for name in names {
+                length += 1;
+                hidden |= name.as_encoded_bytes().starts_with(b".");
+            }
+            priority += self.path_length * length;
+            if hidden {
+                priority += self.hidden;
+            }
+        }
+        priority
+    }
+
+    /// Number of buckets a match climbs thanks to its visit history
+    pub fn boost(&self, frecency: f64) -> u32 {
+        if frecency < 1.0 {
+            return 0;
+        }
+        // Logarithmic so that a handful of visits nudges and daily use dominates
+        (frecency.log2() as u32)
+            .saturating_sub(1)
+            .min(MAX_FRECENCY_BOOST)
+            * self.recency
+    }
+}
+
+impl Ranker for Ranking {
+    /// Boosted bucket first, then the tie-breakers in their configured order
+    fn compare(&self, a: &DirectoryMatch, b: &DirectoryMatch) -> Ordering {
+        let a_priority = self.priority(a);
+        let b_priority = self.priority(b);
+
+        // First sort by priority, with frequently visited directories promoted
+        let a_boosted = a_priority.saturating_sub(self.boost(a.frecency));
+        let b_boosted = b_priority.saturating_sub(self.boost(b.frecency));
+        let boosted_cmp = a_boosted.cmp(&b_boosted);
+        if boosted_cmp != Ordering::Equal {
+            return boosted_cmp;
+        }
+
+        // Within the same boosted bucket, apply the tie-breakers in their configured order
+        for tie_break in &self.tie_breaks {
+            let ordering = match tie_break {
+                // More visited directories come first
+                TieBreak::Frecency => b
+                    .frecency
+                    .partial_cmp(&a.frecency)
+                    .unwrap_or(Ordering::Equal),
+                TieBreak::Priority => a_priority.cmp(&b_priority),
+                // Stronger qualities come first when several share a bucket
+                TieBreak::Quality => a.match_quality.cmp(&b.match_quality),
+                // Multi-term matches whose terms sit closer to the final component come first
+                TieBreak::Spread => a.spread.cmp(&b.spread),
+                // Better fuzzy alignments and closer typos come first
+                TieBreak::Score => b.score.cmp(&a.score),
+                // Shallower first for down matches, closer first for up matches
+                TieBreak::Depth => match a.match_quality {
+                    MatchQuality::ExactUp | MatchQuality::AcronymUp | MatchQuality::PartialUp => {
+                        // For up matches, closer to current (higher depth) comes first
+                        b.depth_from_current.cmp(&a.depth_from_current)
+                    }
+                    MatchQuality::Fuzzy | MatchQuality::Typo => {
+                        // Fuzzy and typo matches mix both directions, so the closest in either direction comes first
+                        a.depth_from_current.abs().cmp(&b.depth_from_current.abs())
+                    }
+                    _ => {
+                        // For down matches, shallower (lower depth) comes first
+                        a.depth_from_current.cmp(&b.depth_from_current)
+                    }
+                },
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn found(depth: i32, quality: MatchQuality, frecency: f64) -> DirectoryMatch {
+        DirectoryMatch {
+            path: PathBuf::from("/start/dir"),
+            depth_from_current: depth,
+            match_quality: quality,
+            match_spans: Vec::new(),
+            frecency,
+            score: 0,
+            spread: 0,
+        }
+    }
+
+    #[test]
+    fn default_buckets_follow_the_built_in_order() {
+        let ranking = Ranking::default();
+        let buckets = [
+            ranking.exact_child,
+            ranking.prefix_child,
+            ranking.acronym_child,
+            ranking.partial_child,
+            ranking.exact_parent,
+            ranking.acronym_parent,
+            ranking.partial_parent,
+            ranking.exact_deeper,
+            ranking.prefix_deeper,
+            ranking.acronym_deeper,
+            ranking.partial_deeper,
+            ranking.fuzzy,
+            ranking.history,
+            ranking.typo,
+        ];
+        assert!(buckets.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(ranking.tie_breaks, DEFAULT_TIE_BREAKS);
+    }
+
+    #[test]
+    fn children_and_deeper_matches_use_their_own_buckets() {
+        let ranking = Ranking::default();
+        let child = found(1, MatchQuality::PrefixDown, 0.0);
+        let deeper = found(3, MatchQuality::PrefixDown, 0.0);
+        assert_eq!(ranking.priority(&child), ranking.prefix_child);
+        assert_eq!(ranking.priority(&deeper), ranking.prefix_deeper);
+        assert_eq!(ranking.compare(&child, &deeper), Ordering::Less);
+    }
+
+    #[test]
+    fn set_understands_every_weight() {
+        let mut ranking = Ranking::default();
+        assert!(ranking.set("acronym-child", "9"));
+        assert!(ranking.set("path-length", "1"));
+        assert!(ranking.set("recency", "0"));
+        assert_eq!(ranking.acronym_child, 9);
+        assert_eq!(ranking.path_length, 1);
+        assert_eq!(ranking.recency, 0);
+
+        assert!(!ranking.set("acronym", "1"));
+        assert!(!ranking.set("fuzzy", "-1"));
+        assert!(!ranking.set("fuzzy", "high"));
+        assert_eq!(ranking.fuzzy, Ranking::default().fuzzy);
+    }
+
+    #[test]
+    fn tiebreak_lists_come_first_and_keep_the_rest() {
+        let mut ranking = Ranking::default();
+        assert!(ranking.set("tiebreak", "depth, score"));
+        assert_eq!(
+            ranking.tie_breaks,
+            [
+                TieBreak::Depth,
+                TieBreak::Score,
+                TieBreak::Frecency,
+                TieBreak::Priority,
+                TieBreak::Quality,
+                TieBreak::Spread,
+            ]
+        );
+        assert!(!ranking.set("tiebreak", "depth,alphabetical"));
+        assert_eq!(ranking.tie_breaks[0], TieBreak::Depth);
+    }
+
+    #[test]
+    fn penalties_push_matches_down() {
+        let ranking = Ranking {
+            depth: 1,
+            path_length: 1,
+            hidden: 5,
+            ..Ranking::default()
+        };
+        let mut hidden = found(2, MatchQuality::ExactDown, 0.0);
+        hidden.path = PathBuf::from("/start/.cache/dir");
+        // The deeper bucket, two levels down, three components and a hidden one
+        assert_eq!(ranking.priority(&hidden), ranking.exact_deeper + 2 + 3 + 5);
+    }
+
+    #[test]
+    fn boost_grows_logarithmically_up_to_a_cap() {
+        let ranking = Ranking::default();
+        assert_eq!(ranking.boost(0.0), 0);
+        assert_eq!(ranking.boost(2.0), 0);
+        assert_eq!(ranking.boost(8.0), 2 * ranking.recency);
+        assert_eq!(ranking.boost(1e9), MAX_FRECENCY_BOOST * ranking.recency);
+
+        let flat = Ranking {
+            recency: 0,
+            ..Ranking::default()
+        };
+        assert_eq!(flat.boost(1e9), 0);
+    }
+
+    #[test]
+    fn visit_history_lifts_a_match_over_a_stronger_one() {
+        let ranking = Ranking::default();
+        let prefix = found(1, MatchQuality::PrefixDown, 0.0);
+        let visited = found(1, MatchQuality::PartialDown, 8.0);
+        assert_eq!(ranking.compare(&visited, &prefix), Ordering::Less);
+
+        let unvisited = found(1, MatchQuality::PartialDown, 0.0);
+        assert_eq!(ranking.compare(&unvisited, &prefix), Ordering::Greater);
+    }
+
+    #[test]
+    fn closer_parents_come_first() {
+        let ranking = Ranking::default();
+        let parent = found(-1, MatchQuality::ExactUp, 0.0);
+        let grandparent = found(-2, MatchQuality::ExactUp, 0.0);
+        assert_eq!(ranking.compare(&parent, &grandparent), Ordering::Less);
+    }
+}
@@ -0,0 +1,1886 @@
+// The search strategies behind `Searcher`. A single term is looked for up the tree, then
+// breadth-first down it, or in the index when one covers the tree; path patterns, absolute
+// paths, bookmarks and multi-term queries each have their own walk. Every strategy reads
+// directories through `walk`, compares names
+// through the same `Matcher`, spends from the same `SearchContext` and hands its matches to
+// `finalize_matches`, which drops duplicates and sorts them with the context's `Ranker`.
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    time::Instant,
+};
+
+use crate::{
+    bookmarks::BookmarkStore,
+    budget::{Limit, SearchBudget},
+    explain, frecency,
+    frecency::VisitStore,
+    ignore::IgnoreRules,
+    index::OpenIndex,
+    is_debug_enabled,
+    matching::{Matcher, NameMatch, NameMatchKind},
+    ranking::Ranker,
+    walk::{Sources, Subdirectory},
+    Error, Result,
+};
+
+/// The tier a match is ranked in, strongest first
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchQuality {
+    ExactUp,     // Exact match up the path - highest priority
+    AcronymUp,   // Word-initial match up the path (-w), ahead of partial matches
+    PartialUp,   // Partial match up the path - second priority
+    ExactDown,   // Exact match down the path - third priority
+    PrefixDown,  // Prefix match down the path - fourth priority
+    AcronymDown, // Word-initial match down the path (-w), between prefix and partial
+    PartialDown, // Partial match down the path - lowest substring priority
+    Fuzzy,       // Scored subsequence match anywhere in the search (-f)
+    History,     // Previously visited directory outside the searched tree
+    Typo,        // Name a few edits from the pattern, only when nothing else matched
+}
+
+/// A directory that matched the query, with what its place in the ranking was based on
+#[derive(Debug, Clone)]
+pub struct DirectoryMatch {
+    pub path: PathBuf,
+    pub depth_from_current: i32, // negative for parents, positive for children
+    pub match_quality: MatchQuality,
+    pub match_spans: Vec<(usize, usize)>, // byte ranges of the final path component that matched
+    pub frecency: f64,                    // visit history score, zero for never-visited directories
+    pub score: i32, // tie-break within a tier: fuzzy alignment, typo edits or case-exact spelling
+    pub spread: usize, // path components spanned by a multi-term match, zero for single terms
+}
+
+impl DirectoryMatch {
+    /// Build a match for a directory whose name matched at `depth` (negative up the tree)
+    pub(crate) fn from_name_match(path: PathBuf, depth: i32, name_match: NameMatch) -> Self {
+        DirectoryMatch {
+            path,
+            depth_from_current: depth,
+            match_quality: name_match.kind.quality(depth < 0),
+            match_spans: name_match.spans,
+            frecency: 0.0,
+            score: name_match.score,
+            spread: 0,
+        }
+    }
+}
+
+/// What one search has spent of its `SearchBudget`, the ranker it sorts with, where it lists
+/// directories from and, for `--explain`, what it left out
+#[derive(Debug)]
+pub(crate) struct SearchContext<'a> {
+    pub budget: &'a SearchBudget,
+    pub ranker: &'a dyn Ranker,
+    pub sources: &'a Sources,
+    start_time: Instant,
+    current_matches: usize,
+    dirs_read: usize,
+    cut: Option<Limit>, // The first budget that stopped the search
+    // Directories left out and why, for `--explain`; `None` when not explaining
+    dropped: Option<Vec<(PathBuf, explain::Reason)>>,
+}
+
+impl<'a> SearchContext<'a> {
+    pub fn new(
+        budget: &'a SearchBudget,
+        ranker: &'a dyn Ranker,
+        sources: &'a Sources,
+        explain: bool,
+    ) -> Self {
+        Self {
+            budget,
+            ranker,
+            sources,
+            start_time: Instant::now(),
+            current_matches: 0,
+            dirs_read: 0,
+            cut: None,
+            dropped: explain.then(Vec::new),
+        }
+    }
+
+    /// The directories left out of the candidates and why; empty unless explaining
+    pub fn take_dropped(&mut self) -> Vec<(PathBuf, explain::Reason)> {
+        self.dropped.take().unwrap_or_default()
+    }
+
+    /// Note that `path` was left out of the candidates
+    fn dropped(&mut self, path: &Path, reason: explain::Reason) {
+        if let Some(dropped) = &mut self.dropped {
+            dropped.push((path.to_path_buf(), reason));
+        }
+    }
+
+    /// Whether the directory at `path`, named `dir_name`, is ignored, noting the rule
+    fn is_ignored(&mut self, path: &Path, dir_name: &str, ignore_patterns: &IgnoreRules) -> bool {
+        let Some(rule) = ignore_patterns.matching_rule(path, dir_name) else {
+            return false;
+        };
+        self.dropped(path, explain::Reason::Ignored(rule.to_string()));
+        true
+    }
+
+    /// The budget that cut the search short, if any did
+    pub fn cut_short(&self) -> Option<Limit> {
+        self.cut
+    }
+
+    /// The budget that has run out, if any
+    fn limit_reached(&self) -> Option<Limit> {
+        if self
+            .budget
+            .results
+            .is_some_and(|max| self.current_matches >= max)
+        {
+            Some(Limit::Results)
+        } else if self.budget.dirs.is_some_and(|max| self.dirs_read >= max) {
+            Some(Limit::Dirs)
+        } else if self
+            .budget
+            .time
+            .is_some_and(|max| self.start_time.elapsed() >= max)
+        {
+            Some(Limit::Time)
+        } else {
+            None
+        }
+    }
+
+    fn should_continue(&mut self) -> bool {
+        match self.limit_reached() {
+            Some(limit) => {
+                self.cut.get_or_insert(limit);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Whether the result budget has room for another match, noting the cut when it has not.
+    /// Indexed searches read no directories, so this is the only budget they spend.
+    fn results_left(&mut self) -> bool {
+        if self
+            .budget
+            .results
+            .is_some_and(|max| self.current_matches >= max)
+        {
+            self.cut.get_or_insert(Limit::Results);
+            return false;
+        }
+        true
+    }
+
+    fn add_match(&mut self) {
+        self.current_matches += 1;
+    }
+
+    /// Charge a level of directories about to be read, trimming it to what the directory
+    /// budget has left. Returns false, with the level emptied, once the search has to stop.
+    fn admit(&mut self, level: &mut Vec<Subdirectory>) -> bool {
+        if !self.should_continue() {
+            let reason = self.stop_reason();
+            for dir in level.drain(..) {
+                self.dropped(&dir.path, reason.clone());
+            }
+            return false;
+        }
+        if let Some(max) = self.budget.dirs {
+            let left = max - self.dirs_read;
+            if level.len() > left {
+                self.cut.get_or_insert(Limit::Dirs);
+                for dir in level.drain(left..) {
+                    self.dropped(&dir.path, explain::Reason::DirLimit);
+                }
+            }
+        }
+        self.dirs_read += level.len();
+        true
+    }
+
+    /// Why `should_continue` turned false, for `--explain`
+    fn stop_reason(&self) -> explain::Reason {
+        match self.limit_reached() {
+            Some(Limit::Results) => explain::Reason::MatchLimit,
+            Some(Limit::Dirs) => explain::Reason::DirLimit,
+            _ => explain::Reason::TimeBudget,
+        }
+    }
+}
+
+/// Resolves the search context by handling relative paths and directory navigation patterns.
+/// Returns (search_directory, pattern) where search_directory is the resolved starting point
+/// and pattern is the remaining search term after resolving relative components.
+pub(crate) fn resolve_search_context(current_dir: &Path, search_term: &str) -> (PathBuf, String) {
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: resolve_search_context: current_dir={}, search_term='{}'",
+            current_dir.display(),
+            search_term
+        );
+    }
+
+    // Handle empty search term
+    if search_term.is_empty() {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Empty search term, returning current directory");
+        }
+        return (current_dir.to_path_buf(), String::new());
+    }
+
+    // Handle pure directory navigation without search pattern
+    if search_term == ".." {
+        if let Some(parent) = current_dir.parent() {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Parent directory navigation to {}", parent.display());
+            }
+            return (parent.to_path_buf(), String::new());
+        } else {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Already at root, staying in current directory");
+            }
+            return (current_dir.to_path_buf(), String::new());
+        }
+    }
+
+    if search_term == "." {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Current directory navigation, staying put");
+        }
+        return (current_dir.to_path_buf(), String::new());
+    }
+
+    // Handle relative paths with patterns like "../foo", "../../bar", etc.
+    if search_term.starts_with("../") || search_term.starts_with("./") {
+        let path = Path::new(search_term);
+        let mut resolved_dir = current_dir.to_path_buf();
+        let mut remaining_pattern = String::new();
+
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Processing relative path pattern");
+        }
+
+        for component in path.components() {
+            match component {
+                std::path::Component::CurDir => {
+                    if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: Current dir component, staying in {}",
+                            resolved_dir.display()
+                        );
+                    }
+                    continue;
+                }
+                std::path::Component::ParentDir => {
+                    if let Some(parent) = resolved_dir.parent() {
+                        if is_debug_enabled() {
+                            eprintln!(
+                                "DEBUG: Parent dir component, moving from {} to {}",
+                                resolved_dir.display(),
+                                parent.display()
+                            );
+                        }
+                        resolved_dir = parent.to_path_buf();
+                    }
+                }
+                std::path::Component::Normal(name) => {
+                    remaining_pattern = name.to_string_lossy().to_string();
+                    if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: Found search pattern '{}' in relative path",
+                            remaining_pattern
+                        );
+                    }
+                    break;
+                }
+                _ => {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: Other path component encountered");
+                    }
+                    break;
+                }
+            }
+        }
+
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Resolved relative path: search_dir={}, pattern='{}'",
+                resolved_dir.display(),
+                remaining_pattern
+            );
+        }
+        return (resolved_dir, remaining_pattern);
+    }
+
+    // Handle multiple levels of parent directory navigation like "../../", "../../../"
+    if search_term.chars().all(|c| c == '.' || c == '/') && search_term.contains("..") {
+        let mut resolved_dir = current_dir.to_path_buf();
+        let path = Path::new(search_term);
+
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Processing multiple parent directory navigation");
+        }
+
+        for component in path.components() {
+            match component {
+                std::path::Component::ParentDir => {
+                    if let Some(parent) = resolved_dir.parent() {
+                        if is_debug_enabled() {
+                            eprintln!(
+                                "DEBUG: Moving up from {} to {}",
+                                resolved_dir.display(),
+                                parent.display()
+                            );
+                        }
+                        resolved_dir = parent.to_path_buf();
+                    }
+                }
+                std::path::Component::CurDir => {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: Staying in current directory");
+                    }
+                }
+                _ => {
+                    break;
+                }
+            }
+        }
+
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Final resolved directory: {}",
+                resolved_dir.display()
+            );
+        }
+        return (resolved_dir, String::new());
+    }
+
+    // For absolute paths and regular patterns, use the original behavior
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: Using current directory for search with pattern '{}'",
+            search_term
+        );
+    }
+    (current_dir.to_path_buf(), search_term.to_string())
+}
+
+/// Resolve an `@name` or `@name/sub/pattern` search term against the bookmarks
+pub(crate) fn find_bookmark_matches(
+    term: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+) -> Result<Vec<DirectoryMatch>> {
+    let (name, rest) = term.split_once('/').unwrap_or((term, ""));
+    let store = BookmarkStore::load();
+    let rest = rest.trim_matches('/');
+
+    let Some(root) = store.get(name) else {
+        // A bare `@prefix` lists every bookmark starting with it, under the query's case policy,
+        // which also drives tab completion
+        let prefixed: Vec<DirectoryMatch> = store
+            .marks()
+            .iter()
+            .filter(|_| rest.is_empty())
+            .filter_map(|(mark, path)| {
+                let name_match = matcher.match_name(mark, name).filter(|name_match| {
+                    matches!(
+                        name_match.kind,
+                        NameMatchKind::Exact | NameMatchKind::Prefix
+                    )
+                })?;
+                path.is_dir().then(|| DirectoryMatch {
+                    // The spans cover the bookmark name, not the directory name
+                    match_spans: Vec::new(),
+                    ..DirectoryMatch::from_name_match(path.clone(), 0, name_match)
+                })
+            })
+            .collect();
+        if prefixed.is_empty() {
+            return Err(Error::NoBookmark(name.to_string()));
+        }
+        return Ok(finalize_matches(prefixed, context));
+    };
+
+    if rest.is_empty() {
+        if !root.is_dir() {
+            return Err(Error::MissingBookmarkTarget {
+                name: name.to_string(),
+                path: root.to_path_buf(),
+            });
+        }
+        return Ok(vec![DirectoryMatch {
+            path: root.to_path_buf(),
+            depth_from_current: 0,
+            match_quality: MatchQuality::ExactDown,
+            match_spans: Vec::new(),
+            frecency: 0.0,
+            score: 0,
+            spread: 0,
+        }]);
+    }
+
+    // The remainder continues as a path pattern below the bookmark, and only below it
+    let mut matches = Vec::new();
+    // Gitignore rules match paths below the bookmark
+    let ignore_patterns = &ignore_patterns.rooted_at(root);
+    let parts: Vec<&str> = rest.split('/').collect();
+    let max_depth = context.budget.path_depth;
+    search_path_parts(
+        root,
+        &parts,
+        0,
+        max_depth,
+        &mut matches,
+        context,
+        matcher,
+        ignore_patterns,
+    );
+    Ok(finalize_matches(matches, context))
+}
+
+/// Look up each match in the visit store and re-rank with frecency blended in.
+/// Visited subdirectories that match are added too, since the down-tree walk stops
+/// early when the immediate children already contain a good match.
+fn apply_frecency(
+    mut matches: Vec<DirectoryMatch>,
+    store: &VisitStore,
+    search_dir: &Path,
+    pattern: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    if store.entries().is_empty() {
+        return matches;
+    }
+
+    if !pattern.is_empty() && !pattern.contains('/') {
+        matches.extend(visited_descendants(
+            store,
+            search_dir,
+            pattern,
+            matcher,
+            ignore_patterns,
+            context,
+        ));
+    }
+
+    let now = frecency::now_secs();
+    for m in &mut matches {
+        m.frecency = store.score(&m.path, now);
+    }
+    finalize_matches(matches, context)
+}
+
+/// True when the filesystem search found nothing better than partial name matches
+fn is_weak_result(matches: &[DirectoryMatch]) -> bool {
+    !matches.iter().any(|m| {
+        matches!(
+            m.match_quality,
+            MatchQuality::ExactUp | MatchQuality::ExactDown | MatchQuality::PrefixDown
+        )
+    })
+}
+
+/// Add visited directories from anywhere on disk whose name matches the pattern as history hits
+fn add_history_matches(
+    mut matches: Vec<DirectoryMatch>,
+    store: &VisitStore,
+    search_dir: &Path,
+    pattern: &str,
+    matcher: &dyn Matcher,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    if pattern.is_empty() {
+        return matches;
+    }
+
+    let now = frecency::now_secs();
+    let before = matches.len();
+    for entry in store.entries() {
+        if entry.path == search_dir || !entry.path.is_dir() {
+            continue;
+        }
+        let Some(name) = entry.path.file_name() else {
+            continue;
+        };
+        let Some(name_match) = matcher.match_name(&name.to_string_lossy(), pattern) else {
+            continue;
+        };
+
+        matches.push(DirectoryMatch {
+            path: entry.path.clone(),
+            depth_from_current: 0,
+            match_quality: MatchQuality::History,
+            match_spans: name_match.spans,
+            frecency: entry.frecency(now),
+            score: name_match.score,
+            spread: 0,
+        });
+    }
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: History fallback added {} candidates",
+            matches.len() - before
+        );
+    }
+    finalize_matches(matches, context)
+}
+
+/// Visited directories below `search_dir` whose name matches the pattern, classified like the down-tree search
+fn visited_descendants(
+    store: &VisitStore,
+    search_dir: &Path,
+    pattern: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    let max_depth = context.budget.depth;
+    let mut visited = Vec::new();
+    for entry in store.entries() {
+        let Ok(relative) = entry.path.strip_prefix(search_dir) else {
+            continue;
+        };
+        let depth = relative.components().count();
+        if depth == 0 || !entry.path.is_dir() {
+            continue;
+        }
+        if depth > max_depth {
+            context.dropped(&entry.path, explain::Reason::TooDeep);
+            continue;
+        }
+
+        // Honor ignore patterns for every directory between the search root and the entry
+        let mut ancestor = search_dir.to_path_buf();
+        if relative.components().any(|component| {
+            ancestor.push(component);
+            context.is_ignored(
+                &ancestor,
+                &component.as_os_str().to_string_lossy(),
+                ignore_patterns,
+            )
+        }) {
+            continue;
+        }
+
+        let Some(name) = entry.path.file_name() else {
+            continue;
+        };
+        let Some(name_match) = matcher.match_name(&name.to_string_lossy(), pattern) else {
+            continue;
+        };
+
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Visited {:?} match: {}",
+                name_match.kind,
+                entry.path.display()
+            );
+        }
+        visited.push(DirectoryMatch::from_name_match(
+            entry.path.clone(),
+            depth as i32,
+            name_match,
+        ));
+    }
+    visited
+}
+
+/// Search around `current_dir`, then blend in the visit history
+pub(crate) fn search_filesystem(
+    current_dir: &Path,
+    search_term: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    // Handle relative paths and standard directory navigation
+    let (search_dir, pattern) = resolve_search_context(current_dir, search_term);
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: Searching for '{}' from {}",
+            pattern,
+            search_dir.display()
+        );
+    }
+
+    // List directories from the index when one covers the search
+    context.sources.open_index(&search_dir);
+
+    // Gitignore rules match paths below the directory the search starts from
+    let ignore_patterns = &ignore_patterns.rooted_at(&search_dir);
+    let matches =
+        find_matching_directories(&search_dir, &pattern, matcher, ignore_patterns, context);
+
+    // Blend visit history into the ranking
+    let store = VisitStore::load();
+    let mut matches = apply_frecency(
+        matches,
+        &store,
+        &search_dir,
+        &pattern,
+        matcher,
+        ignore_patterns,
+        context,
+    );
+
+    // Fall back to remembered directories anywhere on disk when the tree search is empty or weak.
+    // Relative and absolute path patterns are scoped explicitly, so they never jump elsewhere.
+    if !search_term.contains('/') && is_weak_result(&matches) {
+        matches = add_history_matches(matches, &store, &search_dir, &pattern, matcher, context);
+    }
+
+    matches
+}
+
+/// Search for a multi-term query such as `jcd work api v2`: the last term must match the
+/// directory name and the earlier terms must match components of its path, in order
+pub(crate) fn search_terms(
+    current_dir: &Path,
+    terms: &[String],
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: Searching for terms {:?} from {}",
+            terms,
+            current_dir.display()
+        );
+    }
+
+    context.sources.open_index(current_dir);
+    let ignore_patterns = &ignore_patterns.rooted_at(current_dir);
+    let mut matches = find_term_matches(current_dir, terms, matcher, ignore_patterns, context);
+
+    // Like z and autojump, remembered directories anywhere on disk are candidates too
+    let store = VisitStore::load();
+    let before = matches.len();
+    for entry in store.entries() {
+        if entry.path == current_dir
+            || !entry.path.is_dir()
+            || matches.iter().any(|m| m.path == entry.path)
+        {
+            continue;
+        }
+        if let Some((name_match, spread)) = match_terms(&entry.path, terms, matcher) {
+            matches.push(DirectoryMatch {
+                path: entry.path.clone(),
+                depth_from_current: 0,
+                match_quality: MatchQuality::History,
+                match_spans: name_match.spans,
+                frecency: 0.0,
+                score: name_match.score,
+                spread,
+            });
+        }
+    }
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: Visit history added {} multi-term candidates",
+            matches.len() - before
+        );
+    }
+
+    let now = frecency::now_secs();
+    for m in &mut matches {
+        m.frecency = store.score(&m.path, now);
+    }
+    finalize_matches(matches, context)
+}
+
+/// Directories above and below `current_dir` whose full path matches every term
+fn find_term_matches(
+    current_dir: &Path,
+    terms: &[String],
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    let max_depth = i32::try_from(context.budget.depth).unwrap_or(i32::MAX);
+    let max_up = context.budget.up.unwrap_or(usize::MAX);
+    let mut matches = Vec::new();
+
+    for (up, ancestor) in current_dir.ancestors().skip(1).take(max_up).enumerate() {
+        if !context.should_continue() {
+            context.dropped(ancestor, context.stop_reason());
+            break;
+        }
+        if let Some((name_match, spread)) = match_terms(ancestor, terms, matcher) {
+            let mut dir_match = DirectoryMatch::from_name_match(
+                ancestor.to_path_buf(),
+                -(up as i32 + 1),
+                name_match,
+            );
+            dir_match.spread = spread;
+            matches.push(dir_match);
+            context.add_match();
+        }
+    }
+
+    // Unlike the single-term search there is no early stop: a shallow hit for the last term
+    // says nothing about whether the earlier terms match its path
+    let mut level = vec![Subdirectory::start(current_dir)];
+    let mut depth = 0;
+    while !level.is_empty() && depth < max_depth && context.admit(&mut level) {
+        let mut next_level = Vec::new();
+        for Subdirectory { path, link } in context.sources.read_level(&level) {
+            if !context.should_continue() {
+                context.dropped(&path, context.stop_reason());
+                break;
+            }
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            if context.is_ignored(&path, &name.to_string_lossy(), ignore_patterns) {
+                continue;
+            }
+
+            if let Some((name_match, spread)) = match_terms(&path, terms, matcher) {
+                if is_debug_enabled() {
+                    eprintln!(
+                        "DEBUG: Multi-term {:?} match at depth {} (spread {}): {}",
+                        name_match.kind,
+                        depth + 1,
+                        spread,
+                        path.display()
+                    );
+                }
+                let mut dir_match =
+                    DirectoryMatch::from_name_match(path.clone(), depth + 1, name_match);
+                dir_match.spread = spread;
+                matches.push(dir_match);
+                context.add_match();
+            }
+            next_level.push(Subdirectory { path, link });
+        }
+
+        level = next_level;
+        depth += 1;
+    }
+
+    // Whatever is left sits at the depth limit
+    for dir in &level {
+        context.dropped(&dir.path, explain::Reason::DepthLimit);
+    }
+
+    matches
+}
+
+/// Match a multi-term query against a whole path: the last term against the final component
+/// and the earlier terms, in order, against its ancestors. Terms are placed as close to the
+/// final component as possible, and the spread counts the components from the first term's
+/// match down to the final component.
+fn match_terms(path: &Path, terms: &[String], matcher: &dyn Matcher) -> Option<(NameMatch, usize)> {
+    let (last, leading) = terms.split_last()?;
+    let name_match = matcher.match_name(&path.file_name()?.to_string_lossy(), last)?;
+
+    let ancestors: Vec<String> = path
+        .parent()?
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+
+    let mut end = ancestors.len();
+    for term in leading.iter().rev() {
+        end = ancestors[..end]
+            .iter()
+            .rposition(|component| matcher.match_name(component, term).is_some())?;
+    }
+    Some((name_match, ancestors.len() - end))
+}
+
+fn find_matching_directories(
+    current_dir: &Path,
+    search_term: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: find_matching_directories: current_dir={}, search_term='{}', matcher={:?}",
+            current_dir.display(),
+            search_term,
+            matcher
+        );
+    }
+
+    let mut matches = Vec::new();
+
+    // Handle empty search term (pure directory navigation like "..", "../../")
+    if search_term.is_empty() {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Empty search term, returning current directory as match");
+        }
+        matches.push(DirectoryMatch {
+            path: current_dir.to_path_buf(),
+            depth_from_current: 0,
+            match_quality: MatchQuality::ExactDown,
+            match_spans: Vec::new(),
+            frecency: 0.0,
+            score: 0,
+            spread: 0,
+        });
+        return matches;
+    }
+
+    // Handle absolute paths
+    if search_term.starts_with('/') {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Processing absolute path: {}", search_term);
+        }
+        let path = Path::new(search_term);
+
+        if search_term.ends_with('/') {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Absolute path ends with slash - exploring subdirectories");
+            }
+            let dir_path = Path::new(search_term.strip_suffix('/').unwrap_or(search_term));
+            if dir_path.exists() && dir_path.is_dir() {
+                let mut subdir_matches = Vec::new();
+                search_absolute_pattern(dir_path, "", &mut subdir_matches, context, matcher);
+
+                if !subdir_matches.is_empty() {
+                    if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: Found {} subdirectories in {}",
+                            subdir_matches.len(),
+                            dir_path.display()
+                        );
+                    }
+                    matches.extend(subdir_matches);
+                } else {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: No subdirectories found, returning directory itself");
+                    }
+                    matches.push(DirectoryMatch {
+                        path: dir_path.to_path_buf(),
+                        depth_from_current: 0,
+                        match_quality: MatchQuality::ExactDown,
+                        match_spans: Vec::new(),
+                        frecency: 0.0,
+                        score: 0,
+                        spread: 0,
+                    });
+                }
+            } else {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: Directory doesn't exist, treating as pattern search");
+                }
+                let search_term_no_slash = search_term.strip_suffix('/').unwrap_or(search_term);
+                let (search_root, search_pattern) =
+                    find_search_root_and_pattern(search_term_no_slash);
+                if let Some(root) = search_root {
+                    search_absolute_pattern(&root, &search_pattern, &mut matches, context, matcher);
+                }
+            }
+        } else if path.exists() && path.is_dir() {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Absolute path exists exactly, returning it directly");
+            }
+            matches.push(DirectoryMatch {
+                path: path.to_path_buf(),
+                depth_from_current: 0,
+                match_quality: MatchQuality::ExactDown,
+                match_spans: Vec::new(),
+                frecency: 0.0,
+                score: 0,
+                spread: 0,
+            });
+        } else {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Absolute path doesn't exist, finding search root and pattern");
+            }
+            let (search_root, search_pattern) = find_search_root_and_pattern(search_term);
+            if let Some(root) = search_root {
+                if is_debug_enabled() {
+                    eprintln!(
+                        "DEBUG: Searching from root {} for pattern '{}'",
+                        root.display(),
+                        search_pattern
+                    );
+                }
+                search_absolute_pattern(&root, &search_pattern, &mut matches, context, matcher);
+            }
+        }
+        return finalize_matches(matches, context);
+    }
+
+    // Handle path-like patterns (contains '/')
+    if search_term.contains('/') {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Processing path-like pattern with '/'");
+        }
+        search_path_pattern_fast(
+            current_dir,
+            search_term,
+            &mut matches,
+            context,
+            matcher,
+            ignore_patterns,
+        );
+        if !matches.is_empty() {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Found {} matches for path pattern", matches.len());
+            }
+            return finalize_matches(matches, context);
+        }
+    }
+
+    if is_debug_enabled() {
+        eprintln!("DEBUG: Starting comprehensive search - up tree then down tree");
+    }
+
+    // Names within a few typos of the pattern, kept in case nothing matches
+    let mut typos = Vec::new();
+
+    // 1. Search up for exact matches, then partial matches (direct path to root only)
+    let up_matches = search_up_tree_with_priority(
+        current_dir,
+        search_term,
+        matcher,
+        ignore_patterns,
+        &mut typos,
+        context,
+    );
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: Found {} matches searching up tree",
+            up_matches.len()
+        );
+    }
+    matches.extend(up_matches);
+
+    // 2. Search down for all matches (exact and partial) from current directory only, through
+    // the index when one covers it
+    let down_matches = search_down_indexed(
+        current_dir,
+        search_term,
+        matcher,
+        ignore_patterns,
+        &mut typos,
+        context,
+    )
+    .unwrap_or_else(|| {
+        search_down_breadth_first_all(
+            current_dir,
+            search_term,
+            matcher,
+            ignore_patterns,
+            &mut typos,
+            context,
+        )
+    });
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: Found {} matches searching down tree",
+            down_matches.len()
+        );
+    }
+    matches.extend(down_matches);
+
+    // Return all matches sorted by priority
+    if !matches.is_empty() {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Total {} matches found, finalizing", matches.len());
+        }
+        return finalize_matches(matches, context);
+    }
+
+    // 3. Nothing matched: fall back to the scanned names closest to the pattern
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: No matches found, {} names within typo distance",
+            typos.len()
+        );
+    }
+    finalize_matches(typos, context)
+}
+
+fn search_up_tree_with_priority(
+    current_dir: &Path,
+    search_term: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    typos: &mut Vec<DirectoryMatch>,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_up_tree_with_priority: searching for '{}', matcher={:?}",
+            search_term, matcher
+        );
+    }
+
+    let mut exact_matches = Vec::new();
+    let mut partial_matches = Vec::new();
+    let mut current = current_dir;
+    let mut depth = -1;
+
+    while let Some(parent) = current.parent() {
+        if context.budget.up.is_some_and(|up| -depth as usize > up) {
+            break;
+        }
+        if !context.should_continue() {
+            context.dropped(parent, context.stop_reason());
+            break;
+        }
+
+        if let Some(name) = parent.file_name() {
+            let name_str = name.to_string_lossy();
+
+            // Check if this directory should be ignored
+            if context.is_ignored(parent, &name_str, ignore_patterns) {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: Ignoring parent directory: {}", name_str);
+                }
+                current = parent;
+                depth -= 1;
+                continue;
+            }
+
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Checking parent '{}' at depth {}", name_str, depth);
+            }
+
+            if let Some(name_match) = matcher.match_name(&name_str, search_term) {
+                if is_debug_enabled() {
+                    eprintln!(
+                        "DEBUG: {:?} match found: {}",
+                        name_match.kind,
+                        parent.display()
+                    );
+                }
+                let is_exact = name_match.kind == NameMatchKind::Exact;
+                let dir_match =
+                    DirectoryMatch::from_name_match(parent.to_path_buf(), depth, name_match);
+                context.add_match();
+                if is_exact {
+                    exact_matches.push(dir_match);
+                } else {
+                    partial_matches.push(dir_match);
+                }
+            } else if let Some(name_match) = matcher.match_typo(&name_str, search_term) {
+                typos.push(DirectoryMatch::from_name_match(
+                    parent.to_path_buf(),
+                    depth,
+                    name_match,
+                ));
+            }
+        }
+        current = parent;
+        depth -= 1;
+    }
+
+    let mut result = exact_matches;
+    result.extend(partial_matches);
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_up_tree_with_priority completed with {} matches",
+            result.len()
+        );
+    }
+
+    result
+}
+
+fn search_down_breadth_first_all(
+    current_dir: &Path,
+    search_term: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    typos: &mut Vec<DirectoryMatch>,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_down_breadth_first_all: searching for '{}', matcher={:?}",
+            search_term, matcher
+        );
+    }
+
+    let mut all_matches = Vec::new();
+    let max_depth = i32::try_from(context.budget.depth).unwrap_or(i32::MAX);
+
+    // First, search immediate subdirectories (depth 1) to check for early stopping
+    let mut immediate_matches = Vec::new();
+    let mut level = vec![Subdirectory::start(current_dir)];
+    if max_depth == 0 || !context.admit(&mut level) {
+        return all_matches;
+    }
+    level.clear();
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: Processing immediate subdirectories in {}",
+            current_dir.display()
+        );
+    }
+
+    // Process current directory (depth 0) first
+    for Subdirectory { path, link } in context.sources.subdirectories(current_dir) {
+        if !context.should_continue() {
+            context.dropped(&path, context.stop_reason());
+            break;
+        }
+
+        if let Some(name) = path.file_name() {
+            let name_str = name.to_string_lossy();
+
+            // Check if this directory should be ignored
+            if context.is_ignored(&path, &name_str, ignore_patterns) {
+                if is_debug_enabled() {
+                    eprintln!("DEBUG: Ignoring directory: {}", name_str);
+                }
+                continue;
+            }
+
+            // Check for any match in immediate subdirectories
+            if let Some(name_match) = matcher.match_name(&name_str, search_term) {
+                if is_debug_enabled() {
+                    eprintln!(
+                        "DEBUG: Immediate {:?} match: {}",
+                        name_match.kind,
+                        path.display()
+                    );
+                }
+                let dir_match = DirectoryMatch::from_name_match(path.clone(), 1, name_match);
+                immediate_matches.push(dir_match.clone());
+                all_matches.push(dir_match);
+                context.add_match();
+            } else if let Some(name_match) = matcher.match_typo(&name_str, search_term) {
+                typos.push(DirectoryMatch::from_name_match(path.clone(), 1, name_match));
+            }
+
+            // Keep subdirectories for potential deeper search
+            level.push(Subdirectory { path, link });
+        }
+    }
+
+    // If there are exact or prefix matches in immediate subdirectories, return early to avoid deep search
+    let has_good_immediate = immediate_matches.iter().any(|m| {
+        matches!(
+            m.match_quality,
+            MatchQuality::ExactDown | MatchQuality::PrefixDown
+        )
+    });
+    if has_good_immediate {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Found good immediate matches, skipping deep search");
+        }
+        context.dropped(current_dir, explain::Reason::EarlyStop);
+        return finalize_matches(all_matches, context);
+    }
+
+    if is_debug_enabled() {
+        eprintln!("DEBUG: No good immediate matches, continuing with deep search");
+    }
+
+    // Otherwise, continue breadth-first one level at a time, reading each level in parallel
+    let mut depth = 1;
+    while !level.is_empty() && depth < max_depth && context.admit(&mut level) {
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Searching {} directories at depth {}",
+                level.len(),
+                depth
+            );
+        }
+
+        let mut next_level = Vec::new();
+        for Subdirectory { path, link } in context.sources.read_level(&level) {
+            if !context.should_continue() {
+                context.dropped(&path, context.stop_reason());
+                break;
+            }
+
+            if let Some(name) = path.file_name() {
+                let name_str = name.to_string_lossy();
+
+                // Check if this directory should be ignored
+                if context.is_ignored(&path, &name_str, ignore_patterns) {
+                    if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: Ignoring directory at depth {}: {}",
+                            depth + 1,
+                            name_str
+                        );
+                    }
+                    continue;
+                }
+
+                // Check for any match (exact, prefix, partial or fuzzy)
+                if let Some(name_match) = matcher.match_name(&name_str, search_term) {
+                    if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: Deep {:?} match at depth {}: {}",
+                            name_match.kind,
+                            depth + 1,
+                            path.display()
+                        );
+                    }
+                    all_matches.push(DirectoryMatch::from_name_match(
+                        path.clone(),
+                        depth + 1,
+                        name_match,
+                    ));
+                    context.add_match();
+                } else if let Some(name_match) = matcher.match_typo(&name_str, search_term) {
+                    typos.push(DirectoryMatch::from_name_match(
+                        path.clone(),
+                        depth + 1,
+                        name_match,
+                    ));
+                }
+
+                // Collect subdirectories for next level
+                next_level.push(Subdirectory { path, link });
+            }
+        }
+
+        level = next_level;
+        depth += 1;
+    }
+
+    // Whatever is left sits at the depth limit
+    for dir in &level {
+        context.dropped(&dir.path, explain::Reason::DepthLimit);
+    }
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_down_breadth_first_all completed with {} total matches",
+            all_matches.len()
+        );
+    }
+
+    finalize_matches(all_matches, context)
+}
+
+/// Search below `current_dir` through the index covering it instead of walking: every indexed
+/// name is matched, and only the directories between `current_dir` and a match are checked
+/// against their modification times. Nothing is walked, so the depth and time budgets do not
+/// apply and deep targets are found. `None` when no index covers `current_dir`.
+fn search_down_indexed(
+    current_dir: &Path,
+    search_term: &str,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+    typos: &mut Vec<DirectoryMatch>,
+    context: &mut SearchContext,
+) -> Option<Vec<DirectoryMatch>> {
+    let index = context.sources.index()?;
+    let subtree = index.below(current_dir)?;
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_down_indexed: {} indexed directories below {}",
+            subtree.dirs.len(),
+            current_dir.display()
+        );
+    }
+
+    // Directories left unread because they were ignored when the index was built are read now
+    // if this search keeps them, as with -x
+    let mut ignored = HashMap::new();
+    let mut dirs = subtree.dirs;
+    for dir in subtree.unread {
+        if !ignored_below(current_dir, &dir, ignore_patterns, context, &mut ignored) {
+            index.add_tree(&dir, ignore_patterns);
+            dirs.extend(
+                index
+                    .below(&dir)
+                    .map(|below| below.dirs)
+                    .unwrap_or_default(),
+            );
+        }
+    }
+
+    let (mut found, mut near) = match_indexed(dirs, search_term, matcher);
+
+    // Only the matches are checked against the disk, each directory above them once. A changed
+    // directory is read again, which drops matches that are gone and turns up new subdirectories.
+    let mut listings = HashMap::new();
+    let mut gained = Vec::new();
+    found.retain(|(path, _)| {
+        !ignored_below(current_dir, path, ignore_patterns, context, &mut ignored)
+            && still_indexed(index, current_dir, path, &mut listings, &mut gained)
+    });
+    let indexed_near = near.len(); // The typo candidates still to be checked like matches
+
+    // New directories are not in the index yet, so their subtrees are read and added
+    let mut fresh = Vec::new();
+    for dir in gained {
+        if !dir.link {
+            index.add_tree(&dir.path, ignore_patterns);
+            fresh.extend(
+                index
+                    .below(&dir.path)
+                    .map(|below| below.dirs)
+                    .unwrap_or_default(),
+            );
+        }
+        fresh.push(dir);
+    }
+    fresh.retain(|dir| {
+        !ignored_below(
+            current_dir,
+            &dir.path,
+            ignore_patterns,
+            context,
+            &mut ignored,
+        )
+    });
+    let (fresh_found, fresh_near) = match_indexed(fresh, search_term, matcher);
+    found.extend(fresh_found);
+    near.extend(fresh_near);
+
+    // Breadth-first order, as a walk would meet them
+    let base = current_dir.components().count();
+    let depth_of =
+        |path: &Path| i32::try_from(path.components().count() - base).unwrap_or(i32::MAX);
+    found.sort_by(|(a, _), (b, _)| depth_of(a).cmp(&depth_of(b)).then_with(|| a.cmp(b)));
+
+    let mut matches: Vec<DirectoryMatch> = Vec::new();
+    for (path, name_match) in found {
+        let depth = depth_of(&path);
+        // Like the walk, good matches right below `current_dir` make deeper ones irrelevant
+        if depth > 1
+            && matches.iter().any(|m| {
+                m.depth_from_current == 1
+                    && matches!(
+                        m.match_quality,
+                        MatchQuality::ExactDown | MatchQuality::PrefixDown
+                    )
+            })
+        {
+            context.dropped(current_dir, explain::Reason::EarlyStop);
+            break;
+        }
+        if !context.results_left() {
+            context.dropped(&path, explain::Reason::MatchLimit);
+            break;
+        }
+        matches.push(DirectoryMatch::from_name_match(path, depth, name_match));
+        context.add_match();
+    }
+
+    // Names within a few typos only matter when nothing matched, so they are checked only then
+    if matches.is_empty() {
+        for (i, (path, name_match)) in near.into_iter().enumerate() {
+            if i >= indexed_near
+                || !ignored_below(current_dir, &path, ignore_patterns, context, &mut ignored)
+                    && still_indexed(index, current_dir, &path, &mut listings, &mut Vec::new())
+            {
+                let depth = depth_of(&path);
+                typos.push(DirectoryMatch::from_name_match(path, depth, name_match));
+            }
+        }
+    }
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_down_indexed completed with {} matches",
+            matches.len()
+        );
+    }
+    Some(finalize_matches(matches, context))
+}
+
+/// Directories with how their names matched
+type NameMatches = Vec<(PathBuf, NameMatch)>;
+
+/// The indexed directories whose names match `search_term`, and those within a few typos of it
+fn match_indexed(
+    dirs: Vec<Subdirectory>,
+    search_term: &str,
+    matcher: &dyn Matcher,
+) -> (NameMatches, NameMatches) {
+    let mut found = Vec::new();
+    let mut near = Vec::new();
+    for Subdirectory { path, .. } in dirs {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        if let Some(name_match) = matcher.match_name(&name, search_term) {
+            found.push((path, name_match));
+        } else if let Some(name_match) = matcher.match_typo(&name, search_term) {
+            near.push((path, name_match));
+        }
+    }
+    (found, near)
+}
+
+/// Whether `path`, or a directory between `top` and it, is ignored. Matches share most of
+/// their ancestors, so each directory's answer is kept in `seen`.
+fn ignored_below(
+    top: &Path,
+    path: &Path,
+    ignore_patterns: &IgnoreRules,
+    context: &mut SearchContext,
+    seen: &mut HashMap<PathBuf, bool>,
+) -> bool {
+    let Ok(rest) = path.strip_prefix(top) else {
+        return false;
+    };
+    let mut dir = top.to_path_buf();
+    for component in rest.components() {
+        dir.push(component);
+        let ignored = match seen.get(&dir) {
+            Some(&ignored) => ignored,
+            None => {
+                let name = component.as_os_str().to_string_lossy();
+                let ignored = context.is_ignored(&dir, &name, ignore_patterns);
+                seen.insert(dir.clone(), ignored);
+                ignored
+            }
+        };
+        if ignored {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether every directory from `top` down to `path` still lists the next one, refreshing
+/// the directories whose modification time changed since they were indexed. Listings are kept
+/// in `seen`, and the subdirectories a refreshed directory gained are added to `gained`.
+fn still_indexed(
+    index: &OpenIndex,
+    top: &Path,
+    path: &Path,
+    seen: &mut HashMap<PathBuf, Option<Vec<Subdirectory>>>,
+    gained: &mut Vec<Subdirectory>,
+) -> bool {
+    let Ok(rest) = path.strip_prefix(top) else {
+        return false;
+    };
+    let mut dir = top.to_path_buf();
+    for component in rest.components() {
+        let listing = seen.entry(dir.clone()).or_insert_with(|| {
+            index.refresh(&dir).map(|(children, new)| {
+                gained.extend(new);
+                children
+            })
+        });
+        let child = dir.join(component);
+        if !listing
+            .as_ref()
+            .is_some_and(|children| children.iter().any(|listed| listed.path == child))
+        {
+            return false;
+        }
+        dir = child;
+    }
+    true
+}
+
+/// Remove duplicates and sort the matches with `ranker`, best first
+fn finalize_matches(
+    mut matches: Vec<DirectoryMatch>,
+    context: &mut SearchContext,
+) -> Vec<DirectoryMatch> {
+    if is_debug_enabled() {
+        eprintln!("DEBUG: finalize_matches: input {} matches", matches.len());
+        for (i, m) in matches.iter().enumerate() {
+            eprintln!(
+                "DEBUG:   [{}] {:?} depth={} path={}",
+                i,
+                m.match_quality,
+                m.depth_from_current,
+                m.path.display()
+            );
+        }
+    }
+
+    // Remove duplicates based on path
+    matches.sort_by(|a, b| a.path.cmp(&b.path));
+    matches.dedup_by(|a, b| {
+        let duplicate = a.path == b.path;
+        if duplicate {
+            context.dropped(&a.path, explain::Reason::Duplicate);
+        }
+        duplicate
+    });
+
+    if is_debug_enabled() {
+        eprintln!("DEBUG: After dedup: {} matches", matches.len());
+    }
+
+    let ranker = context.ranker;
+    matches.sort_by(|a, b| ranker.compare(a, b));
+
+    if is_debug_enabled() {
+        eprintln!("DEBUG: After sorting: {} matches", matches.len());
+        for (i, m) in matches.iter().enumerate() {
+            eprintln!(
+                "DEBUG:   [{}] {:?} depth={} path={}",
+                i,
+                m.match_quality,
+                m.depth_from_current,
+                m.path.display()
+            );
+        }
+    }
+
+    matches
+}
+
+fn search_path_pattern_fast(
+    current_dir: &Path,
+    search_term: &str,
+    matches: &mut Vec<DirectoryMatch>,
+    context: &mut SearchContext,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+) {
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_path_pattern_fast: current_dir={}, search_term='{}', matcher={:?}",
+            current_dir.display(),
+            search_term,
+            matcher
+        );
+    }
+
+    let parts: Vec<&str> = search_term.split('/').collect();
+    if parts.is_empty() || !context.should_continue() {
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: search_path_pattern_fast: early exit - parts empty or context expired"
+            );
+        }
+        return;
+    }
+
+    let first_part = parts[0];
+    let remaining_parts = &parts[1..];
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_path_pattern_fast: split into first_part='{}', remaining_parts={:?}",
+            first_part, remaining_parts
+        );
+    }
+
+    // Search for the first part in current directory and subdirectories
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_path_pattern_fast: starting recursive search down from current dir"
+        );
+    }
+    search_path_parts(
+        current_dir,
+        &parts,
+        0,
+        context.budget.path_depth,
+        matches,
+        context,
+        matcher,
+        ignore_patterns,
+    );
+
+    // Also search up the tree for the first part, as far as the budget allows
+    if is_debug_enabled() {
+        eprintln!("DEBUG: search_path_pattern_fast: starting search up the tree");
+    }
+    let mut current = current_dir;
+    let mut depth = -1;
+    let mut up_count = 0;
+
+    while let Some(parent) = current.parent() {
+        let up_limit = context.budget.up.is_some_and(|up| up_count >= up);
+        if up_limit || !context.should_continue() {
+            if !up_limit {
+                context.dropped(parent, context.stop_reason());
+            }
+            if is_debug_enabled() {
+                eprintln!("DEBUG: search_path_pattern_fast: stopping up search - context expired or max up count reached");
+            }
+            break;
+        }
+
+        if let Some(name) = parent.file_name() {
+            let name_str = name.to_string_lossy();
+            if is_debug_enabled() {
+                eprintln!(
+                    "DEBUG: search_path_pattern_fast: checking parent '{}' at depth {}",
+                    name_str, depth
+                );
+            }
+
+            // Matching parents are skipped when ignored, as in the plain upward search
+            let name_match = matcher
+                .match_name(&name_str, first_part)
+                .filter(|_| !context.is_ignored(parent, &name_str, ignore_patterns));
+            if let Some(name_match) = name_match {
+                if is_debug_enabled() {
+                    eprintln!(
+                        "DEBUG: search_path_pattern_fast: parent '{}' matches pattern '{}'",
+                        name_str, first_part
+                    );
+                }
+
+                if remaining_parts.is_empty() {
+                    let dir_match =
+                        DirectoryMatch::from_name_match(parent.to_path_buf(), depth, name_match);
+
+                    if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: search_path_pattern_fast: adding up match {:?} for {}",
+                            dir_match.match_quality,
+                            parent.display()
+                        );
+                    }
+
+                    matches.push(dir_match);
+                    context.add_match();
+                } else {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: search_path_pattern_fast: recursing from parent for remaining patterns");
+                    }
+                    search_path_parts(
+                        parent,
+                        remaining_parts,
+                        depth,
+                        context.budget.path_depth.saturating_sub(1),
+                        matches,
+                        context,
+                        matcher,
+                        ignore_patterns,
+                    );
+                }
+            }
+        }
+        current = parent;
+        depth -= 1;
+        up_count += 1;
+    }
+
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_path_pattern_fast: completed with {} total matches",
+            matches.len()
+        );
+    }
+}
+
+/// Match the `/`-separated `parts` of a path pattern below `start`, reading the tree one level
+/// at a time like the other downward searches. Each part may match any number of levels below
+/// the one before it. `start` sits at `base_depth`, and at most `max_depth` levels are read.
+#[allow(clippy::too_many_arguments)]
+fn search_path_parts(
+    start: &Path,
+    parts: &[&str],
+    base_depth: i32,
+    max_depth: usize,
+    matches: &mut Vec<DirectoryMatch>,
+    context: &mut SearchContext,
+    matcher: &dyn Matcher,
+    ignore_patterns: &IgnoreRules,
+) {
+    if is_debug_enabled() {
+        eprintln!(
+            "DEBUG: search_path_parts: start={}, parts={:?}, base_depth={}, max_depth={}, matcher={:?}",
+            start.display(),
+            parts,
+            base_depth,
+            max_depth,
+            matcher
+        );
+    }
+
+    // Each directory of the level, with the indexes of the parts it may still match below it
+    let mut level: Vec<(Subdirectory, Vec<usize>)> = vec![(Subdirectory::start(start), vec![0])];
+    let mut depth = base_depth;
+    let mut levels_read = 0;
+    while !level.is_empty() && levels_read < max_depth {
+        let mut dirs: Vec<Subdirectory> = level.iter().map(|(dir, _)| dir.clone()).collect();
+        if !context.admit(&mut dirs) {
+            return;
+        }
+        level.truncate(dirs.len());
+        depth += 1;
+        levels_read += 1;
+
+        let mut next_level = Vec::new();
+        for ((_, pending), children) in level.iter().zip(context.sources.read_each(&dirs)) {
+            for Subdirectory { path, link } in children {
+                if !context.should_continue() {
+                    context.dropped(&path, context.stop_reason());
+                    return;
+                }
+                let Some(name) = path.file_name() else {
+                    continue;
+                };
+                let name_str = name.to_string_lossy();
+                if context.is_ignored(&path, &name_str, ignore_patterns) {
+                    if is_debug_enabled() {
+                        eprintln!("DEBUG: search_path_parts: ignoring directory: {}", name_str);
+                    }
+                    continue;
+                }
+
+                // Every pending part is looked for further down; a matched part moves on to
+                // the next one, and matching the last part makes a result
+                let mut next = pending.clone();
+                let mut found = None;
+                for &part in pending {
+                    if let Some(name_match) = matcher.match_name(&name_str, parts[part]) {
+                        if part + 1 == parts.len() {
+                            found = Some(name_match);
+                        } else {
+                            next.push(part + 1);
+                        }
+                    }
+                }
+                if let Some(name_match) = found {
+                    let dir_match =
+                        DirectoryMatch::from_name_match(path.clone(), depth, name_match);
+                    if is_debug_enabled() {
+                        eprintln!(
+                            "DEBUG: search_path_parts: adding match {:?} for {}",
+                            dir_match.match_quality,
+                            path.display()
+                        );
+                    }
+                    matches.push(dir_match);
+                    context.add_match();
+                }
+                next.sort_unstable();
+                next.dedup();
+                next_level.push((Subdirectory { path, link }, next));
+            }
+        }
+        level = next_level;
+    }
+
+    // Whatever is left sits at the depth limit
+    for (dir, _) in &level {
+        context.dropped(&dir.path, explain::Reason::DepthLimit);
+    }
+}
+
+fn search_absolute_pattern(
+    parent_dir: &Path,
+    pattern: &str,
+    matches: &mut Vec<DirectoryMatch>,
+    context: &mut SearchContext,
+    matcher: &dyn Matcher,
+) {
+    let mut immediate_matches: Vec<DirectoryMatch> = Vec::new();
+    let max_depth = i32::try_from(context.budget.depth).unwrap_or(i32::MAX);
+    let mut level = vec![Subdirectory::start(parent_dir)];
+    if max_depth == 0 || !context.admit(&mut level) {
+        return;
+    }
+    level.clear();
+
+    // First, search immediate subdirectories (depth 1) to check for early stopping
+    for Subdirectory { path, link } in context.sources.subdirectories(parent_dir) {
+        if !context.should_continue() {
+            context.dropped(&path, context.stop_reason());
+            break;
+        }
+        if let Some(name) = path.file_name() {
+            let name_str = name.to_string_lossy();
+            // Check for immediate matches
+            if let Some(name_match) = matcher.match_name(&name_str, pattern) {
+                let dir_match = DirectoryMatch::from_name_match(path.clone(), 1, name_match);
+                immediate_matches.push(dir_match.clone());
+                matches.push(dir_match);
+                context.add_match();
+            }
+
+            // Keep subdirectories for potential deeper search
+            level.push(Subdirectory { path, link });
+        }
+    }
+
+    // If there are any matches in immediate subdirectories, return early to avoid deep search
+    // This prioritizes local matches over distant ones (same logic as relative paths).
+    // Fuzzy matches are too loose to cut the search short.
+    if immediate_matches
+        .iter()
+        .any(|m| m.match_quality != MatchQuality::Fuzzy)
+    {
+        context.dropped(parent_dir, explain::Reason::EarlyStop);
+        return;
+    }
+
+    // Otherwise, continue breadth-first one level at a time, reading each level in parallel
+    let mut depth = 1;
+    while !level.is_empty() && depth < max_depth && context.admit(&mut level) {
+        let mut next_level = Vec::new();
+        for Subdirectory { path, link } in context.sources.read_level(&level) {
+            if !context.should_continue() {
+                context.dropped(&path, context.stop_reason());
+                break;
+            }
+            if let Some(name) = path.file_name() {
+                let name_str = name.to_string_lossy();
+                // Check for pattern match at deeper levels
+                if let Some(name_match) = matcher.match_name(&name_str, pattern) {
+                    matches.push(DirectoryMatch::from_name_match(
+                        path.clone(),
+                        depth,
+                        name_match,
+                    ));
+                    context.add_match();
+                }
+
+                // Keep subdirectories for next level search
+                next_level.push(Subdirectory { path, link });
+            }
+        }
+
+        level = next_level;
+        depth += 1;
+    }
+
+    // Whatever is left sits at the depth limit
+    for dir in &level {
+        context.dropped(&dir.path, explain::Reason::DepthLimit);
+    }
+}
+
+fn find_search_root_and_pattern(search_term: &str) -> (Option<PathBuf>, String) {
+    let path = Path::new(search_term);
+    let mut current = path;
+
+    // Walk up the path to find the longest existing prefix
+    while let Some(parent) = current.parent() {
+        if parent.exists() && parent.is_dir() {
+            // Found existing parent directory
+            // The search pattern is the first component after this parent
+            let remaining = path.strip_prefix(parent).unwrap();
+            let mut components = remaining.components();
+            if let Some(first_component) = components.next() {
+                let pattern = first_component.as_os_str().to_string_lossy().to_string();
+                return (Some(parent.to_path_buf()), pattern);
+            }
+        }
+        current = parent;
+    }
+
+    // If we get here, even root doesn't exist (shouldn't happen on Unix)
+    // Fall back to searching from root with the first component as pattern
+    let first_component = Path::new(search_term)
+        .components()
+        .nth(1) // Skip the root component "/"
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .unwrap_or_else(|| search_term.trim_start_matches('/').to_string());
+    (Some(PathBuf::from("/")), first_component)
+}
@@ -0,0 +1,321 @@
+// The library's entry point. `SearchOptions` gathers what the jcd command sets with flags and
+// config keys, and a `Searcher` runs queries with them from any starting directory. Problems
+// with the query come back as `Error`s; the search itself never fails, it only finds less.
+// Everything a search touches besides its options, from the ignore file to the daemon
+// connection and the `--explain` notes, belongs to that one search; only the directory
+// indexes are kept by the `Searcher`, so each is read once.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    budget::{Limit, SearchBudget},
+    explain,
+    ignore::{load_ignore_patterns, IgnoreRules},
+    index::Indexes,
+    is_debug_enabled,
+    matching::{CasePolicy, MatchOptions, Matcher},
+    normalize,
+    normalize::Normalization,
+    pattern,
+    pattern::{CompiledPatterns, PatternMode},
+    ranking::{Ranker, Ranking},
+    search::{
+        find_bookmark_matches, search_filesystem, search_terms, DirectoryMatch, SearchContext,
+    },
+    walk::Sources,
+    Error, Result,
+};
+
+/// How a `Searcher` compares names and ranks matches; the defaults are those of `jcd` without
+/// any flags or config file
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    pub case: CasePolicy,             // -i, -s or -S
+    pub fuzzy: bool,                  // Scored subsequence matching (-f)
+    pub acronym: bool,                // Word-initial matching (-w)
+    pub mode: Option<PatternMode>,    // -F, -r or -g; `None` tries glob-like terms as globs
+    pub normalization: Normalization, // -u or -a
+    pub bypass_ignore: bool,          // Search ignored directories too (-x)
+    pub budget: SearchBudget,         // --max-<budget> and search.<budget>
+    pub ranking: Ranking,             // rank.* config keys
+    pub use_index: bool,              // List directories from the index and daemon (--no-index)
+    pub explain: bool,                // Note dropped directories in the results (--explain)
+    // Library-only replacements for the built-in name comparison and for `ranking`
+    pub matcher: Option<Arc<dyn Matcher>>,
+    pub ranker: Option<Arc<dyn Ranker>>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            case: CasePolicy::Smart,
+            fuzzy: false,
+            acronym: false,
+            mode: None,
+            normalization: Normalization::None,
+            bypass_ignore: false,
+            budget: SearchBudget::default(),
+            ranking: Ranking::default(),
+            use_index: true,
+            explain: false,
+            matcher: None,
+            ranker: None,
+        }
+    }
+}
+
+/// What a search found
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+    pub matches: Vec<DirectoryMatch>, // Best first
+    pub cut_short: Option<Limit>,     // The budget that stopped the search early, if one did
+    pub ignore_file: Option<PathBuf>, // The ignore file the search honoured
+    // Directories left out of the matches and why; filled in only with `explain`
+    pub dropped: Vec<(PathBuf, explain::Reason)>,
+}
+
+/// Runs searches with one set of options; cheap to clone and to share between threads.
+/// Directory indexes are read the first time a search needs them, and clones share them.
+#[derive(Debug, Clone)]
+pub struct Searcher {
+    options: SearchOptions,
+    indexes: Arc<Indexes>,
+}
+
+impl Searcher {
+    pub fn new(options: SearchOptions) -> Self {
+        Searcher {
+            options,
+            indexes: Arc::default(),
+        }
+    }
+
+    pub fn options(&self) -> &SearchOptions {
+        &self.options
+    }
+
+    /// Find and rank the directories matching `terms` around `current_dir`. A single term is
+    /// a name, a path pattern, an absolute path or an `@bookmark`; with several terms, each
+    /// must match a component of the path in order and the last one the directory's name.
+    pub fn search<S: AsRef<str>>(&self, terms: &[S], current_dir: &Path) -> Result<SearchResults> {
+        let terms: Vec<String> = terms.iter().map(|term| term.as_ref().to_string()).collect();
+        if terms.is_empty() {
+            return Err(Error::NoSearchTerm);
+        }
+        let search_term = terms.join(" ");
+
+        // Terms with glob characters are globs unless a mode was chosen; a name such as
+        // `[archive]` that only looks like one is searched literally when the glob fails
+        let guessed_glob = self.options.mode.is_none()
+            && self.options.matcher.is_none()
+            && pattern::looks_like_glob(&search_term);
+        let mode = match self.options.mode {
+            Some(mode) => mode,
+            None if guessed_glob => PatternMode::Glob,
+            None => PatternMode::Literal,
+        };
+        let options = match self.match_options(&terms, &search_term, mode) {
+            Err(_) if guessed_glob => {
+                self.match_options(&terms, &search_term, PatternMode::Literal)?
+            }
+            options => options?,
+        };
+
+        // Project-local ignore files are looked for where the search starts
+        let ignore_patterns = if self.options.bypass_ignore {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Bypassing ignore patterns (-x flag)");
+            }
+            IgnoreRules::default()
+        } else {
+            load_ignore_patterns(current_dir)
+        };
+
+        let search = |options: &MatchOptions| {
+            self.run(&terms, &search_term, current_dir, options, &ignore_patterns)
+        };
+        let mut results = search(&options)?;
+        if guessed_glob && options.mode == PatternMode::Glob && results.matches.is_empty() {
+            if is_debug_enabled() {
+                eprintln!("DEBUG: Glob found nothing, searching for it literally");
+            }
+            results = search(&self.match_options(&terms, &search_term, PatternMode::Literal)?)?;
+        }
+        Ok(results)
+    }
+
+    /// Run one search with settled name comparison options
+    fn run(
+        &self,
+        terms: &[String],
+        search_term: &str,
+        current_dir: &Path,
+        options: &MatchOptions,
+        ignore_patterns: &IgnoreRules,
+    ) -> Result<SearchResults> {
+        // A running `jcd daemon` lists the directories it watches
+        let sources = Sources::new(self.options.use_index, Arc::clone(&self.indexes));
+
+        let matcher = self.options.matcher.as_deref().unwrap_or(options);
+        let ranker = self
+            .options
+            .ranker
+            .as_deref()
+            .unwrap_or(&self.options.ranking);
+        let mut context =
+            SearchContext::new(&self.options.budget, ranker, &sources, self.options.explain);
+        let matches = if terms.len() > 1 {
+            search_terms(current_dir, terms, matcher, ignore_patterns, &mut context)
+        } else if let Some(mark) = search_term.strip_prefix('@') {
+            // `@name` jumps to a bookmark, `@name/sub` continues as a path pattern below it
+            find_bookmark_matches(mark, matcher, ignore_patterns, &mut context)?
+        } else {
+            search_filesystem(
+                current_dir,
+                search_term,
+                matcher,
+                ignore_patterns,
+                &mut context,
+            )
+        };
+
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Found {} matches", matches.len());
+        }
+        sources.save_index();
+
+        Ok(SearchResults {
+            matches,
+            cut_short: context.cut_short(),
+            ignore_file: ignore_patterns.file().map(Path::to_path_buf),
+            dropped: context.take_dropped(),
+        })
+    }
+
+    /// Name comparison settings for this query; regex and glob terms are compiled once per path
+    /// segment, so invalid ones are rejected before searching and every comparison reuses them
+    fn match_options(
+        &self,
+        terms: &[String],
+        search_term: &str,
+        mode: PatternMode,
+    ) -> Result<MatchOptions> {
+        let case_sensitive = self.options.case.is_case_sensitive(search_term, mode);
+        if is_debug_enabled() {
+            eprintln!(
+                "DEBUG: Case policy {:?}, case sensitive: {}",
+                self.options.case, case_sensitive
+            );
+        }
+
+        let compiled = if mode == PatternMode::Literal {
+            CompiledPatterns::default()
+        } else {
+            let segments: Vec<&str> = terms
+                .iter()
+                .flat_map(|term| term.split('/'))
+                .filter(|s| !s.is_empty())
+                .collect();
+            // Normalized searches compare the normalized spelling of each segment
+            let normalized: Vec<String> = match self.options.normalization {
+                Normalization::None => Vec::new(),
+                normalization => segments
+                    .iter()
+                    .map(|segment| normalize::normalize(segment, normalization, false))
+                    .collect(),
+            };
+            let patterns = segments
+                .iter()
+                .copied()
+                .chain(normalized.iter().map(String::as_str));
+            CompiledPatterns::new(patterns, mode, case_sensitive).map_err(|(pattern, e)| {
+                Error::InvalidPattern {
+                    pattern,
+                    message: e.to_string(),
+                }
+            })?
+        };
+
+        Ok(MatchOptions {
+            case_sensitive,
+            fuzzy: self.options.fuzzy,
+            acronym: self.options.acronym,
+            mode,
+            normalization: self.options.normalization,
+            compiled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    /// A scratch directory under the system temp directory, removed again on drop
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("jcd_unit_{}_{}", name, process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Scratch(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn explaining() -> Searcher {
+        Searcher::new(SearchOptions {
+            explain: true,
+            use_index: false,
+            ..SearchOptions::default()
+        })
+    }
+
+    #[test]
+    fn ignore_file_is_found_where_the_search_starts() {
+        let scratch = Scratch::new("ignore_file");
+        fs::create_dir_all(scratch.0.join("skipped/unitneedle")).unwrap();
+        fs::create_dir_all(scratch.0.join("kept/unitneedle")).unwrap();
+        fs::write(scratch.0.join(".jcdignore"), "^skipped$\n").unwrap();
+
+        let results = explaining().search(&["unitneedle"], &scratch.0).unwrap();
+        let paths: Vec<_> = results.matches.iter().map(|m| m.path.clone()).collect();
+        assert_eq!(paths, vec![scratch.0.join("kept/unitneedle")]);
+        assert_eq!(results.ignore_file, Some(scratch.0.join(".jcdignore")));
+        assert!(results.dropped.contains(&(
+            scratch.0.join("skipped"),
+            explain::Reason::Ignored("^skipped$".to_string())
+        )));
+    }
+
+    #[test]
+    fn explain_notes_start_over_for_each_search() {
+        let scratch = Scratch::new("explain");
+        fs::create_dir_all(scratch.0.join("skipped/unitneedle")).unwrap();
+        fs::write(scratch.0.join(".jcdignore"), "^skipped$\n").unwrap();
+
+        let searcher = explaining();
+        let first = searcher.search(&["unitneedle"], &scratch.0).unwrap();
+        let second = searcher.search(&["unitneedle"], &scratch.0).unwrap();
+        assert!(!first.dropped.is_empty());
+        assert_eq!(first.dropped, second.dropped);
+
+        let quiet = Searcher::new(SearchOptions::default());
+        assert!(quiet
+            .search(&["unitneedle"], &scratch.0)
+            .unwrap()
+            .dropped
+            .is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+// Typo tolerance for searches that find nothing: directory names within a small
+// Damerau-Levenshtein distance of the pattern (insertions, deletions, substitutions and
+// swaps of adjacent characters) are offered as a last, low-confidence tier, so `serivce`
+// still finds `service`.
+
+/// Edits allowed for a pattern of this many characters. Short patterns get none,
+/// since almost any short name is a couple of edits away from them.
+pub fn max_edits(pattern_len: usize) -> usize {
+    match pattern_len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Optimal string alignment distance between `a` and `b`, or `None` when it exceeds `limit`
+pub fn distance(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+
+    // Three rolling rows: two back for transpositions, the previous and the current one
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        let mut row_min = current[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(before[j - 2] + 1);
+            }
+            current[j] = best;
+            row_min = row_min.min(best);
+        }
+        // Every later row is at least the smallest entry of this one
+        if row_min > limit {
+            return None;
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let result = previous[b.len()];
+    (result <= limit).then_some(result)
+}
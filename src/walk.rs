@@ -0,0 +1,200 @@
+// Directory reads for the breadth-first searches. A whole level of the tree is read at once
+// on rayon's work-stealing pool, so on network file systems and large checkouts the
+// `read_dir` latency of many directories overlaps instead of adding up. Every directory's
+// entries are sorted by name and levels keep their parents' order, so results never
+// depend on which thread finished first.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use rayon::prelude::*;
+
+use crate::{daemon, index, is_debug_enabled, path_bytes, path_from_bytes};
+
+/// Size the thread pool used for directory reads; zero keeps rayon's default of one per CPU.
+/// Only the first call has any effect.
+pub fn set_threads(threads: usize) {
+    if let Err(e) = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+    {
+        if is_debug_enabled() {
+            eprintln!("DEBUG: Cannot size the walker thread pool: {}", e);
+        }
+    }
+}
+
+/// Whether `entry` is a directory. The type usually comes straight from the directory listing;
+/// only symlinks, and entries whose type the file system does not report, cost a `stat`.
+/// Symlinks count when they point at a directory.
+pub fn is_dir(entry: &fs::DirEntry) -> bool {
+    match entry.file_type() {
+        Ok(file_type) if !file_type.is_symlink() => file_type.is_dir(),
+        _ => fs::metadata(entry.path()).is_ok_and(|metadata| metadata.is_dir()),
+    }
+}
+
+/// Whether `entry` is a symlink. Links to directories can be matched but are never descended
+/// into, which keeps link loops and links into `/proc` or `/sys` out of the search.
+pub fn is_link(entry: &fs::DirEntry) -> bool {
+    entry
+        .file_type()
+        .is_ok_and(|file_type| file_type.is_symlink())
+}
+
+/// A directory to search, with whether it was listed as a symlink. Links are matched but
+/// never read, and the bit travels with the path so the walk needs no `lstat` to know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subdirectory {
+    pub path: PathBuf,
+    pub link: bool,
+}
+
+impl Subdirectory {
+    /// A directory the search starts from, such as the current one; it is read even if it
+    /// was reached through a link
+    pub fn start(path: &Path) -> Self {
+        Subdirectory {
+            path: path.to_path_buf(),
+            link: false,
+        }
+    }
+
+    /// The entry as the index and the daemon store it: its name, followed by a `/`, which
+    /// no name can contain, when it is a link
+    pub fn listed_name(&self) -> Vec<u8> {
+        let mut name =
+            path_bytes(Path::new(self.path.file_name().unwrap_or_default())).into_owned();
+        if self.link {
+            name.push(b'/');
+        }
+        name
+    }
+
+    /// The entry of `dir` that `listed_name` wrote as `name`
+    pub fn from_listed_name(dir: &Path, name: &[u8]) -> Self {
+        let (name, link) = match name.strip_suffix(b"/") {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        Subdirectory {
+            path: dir.join(path_from_bytes(name)),
+            link,
+        }
+    }
+}
+
+/// Where one search lists directories from: the running daemon, then the directory index
+/// covering the search, then the disk
+#[derive(Debug)]
+pub struct Sources {
+    daemon: daemon::Connection,
+    indexes: Arc<index::Indexes>,           // Loaded once per `Searcher`
+    index: OnceLock<Arc<index::OpenIndex>>, // The one covering the search, once it is opened
+    use_index: bool,                        // `--no-index` lists every directory from disk
+}
+
+impl Sources {
+    /// Connect to the daemon, if one is running, unless `use_index` is off
+    pub fn new(use_index: bool, indexes: Arc<index::Indexes>) -> Self {
+        Sources {
+            daemon: if use_index {
+                daemon::Connection::open()
+            } else {
+                daemon::Connection::default()
+            },
+            indexes,
+            index: OnceLock::new(),
+            use_index,
+        }
+    }
+
+    /// List directories below `dir` from its index, if it or an ancestor is indexed
+    pub fn open_index(&self, dir: &Path) {
+        if self.use_index {
+            if let Some(index) = self.indexes.covering(dir) {
+                let _ = self.index.set(index);
+            }
+        }
+    }
+
+    /// The index the search opened, if one covers it
+    pub fn index(&self) -> Option<&index::OpenIndex> {
+        self.index.get().map(Arc::as_ref)
+    }
+
+    /// Write back what the search refreshed of the index
+    pub fn save_index(&self) {
+        if let Some(index) = self.index() {
+            index.save();
+        }
+    }
+
+    /// Subdirectories of `dir`, sorted by name
+    pub fn subdirectories(&self, dir: &Path) -> Vec<Subdirectory> {
+        let listed = self.daemon.listings(&[dir]).pop().flatten();
+        listed.unwrap_or_else(|| self.list_locally(dir))
+    }
+
+    /// Subdirectories of `dir` the daemon had no listing for
+    fn list_locally(&self, dir: &Path) -> Vec<Subdirectory> {
+        self.index()
+            .and_then(|index| index.subdirectories(dir))
+            .unwrap_or_else(|| read_subdirectories(dir))
+    }
+
+    /// Subdirectories of every directory in `level`, read in parallel. The result lists each
+    /// directory's children in the order of `level`, each sorted by name. Symlinked
+    /// directories in `level` are not read.
+    pub fn read_level(&self, level: &[Subdirectory]) -> Vec<Subdirectory> {
+        self.read_each(level).into_iter().flatten().collect()
+    }
+
+    /// Like `read_level`, but keeps the children of each directory of `level` apart;
+    /// symlinked directories list no children
+    pub fn read_each(&self, level: &[Subdirectory]) -> Vec<Vec<Subdirectory>> {
+        let dirs: Vec<&Path> = level
+            .iter()
+            .filter(|dir| !dir.link)
+            .map(|dir| dir.path.as_path())
+            .collect();
+        // The daemon lists the whole level in one round trip; what it does not watch is read here
+        let listed = self.daemon.listings(&dirs);
+        let mut children = dirs
+            .into_par_iter()
+            .zip(listed)
+            .map(|(dir, listed)| listed.unwrap_or_else(|| self.list_locally(dir)))
+            .collect::<Vec<_>>()
+            .into_iter();
+        level
+            .iter()
+            .map(|dir| {
+                if dir.link {
+                    Vec::new()
+                } else {
+                    children.next().unwrap_or_default()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Subdirectories of `dir`, sorted by name, read from disk
+pub fn read_subdirectories(dir: &Path) -> Vec<Subdirectory> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+    entries
+        .into_iter()
+        .filter(is_dir)
+        .map(|entry| Subdirectory {
+            link: is_link(&entry),
+            path: entry.path(),
+        })
+        .collect()
+}
@@ -0,0 +1,71 @@
+#!/bin/bash
+# Times a down-tree search over a generated tree that is mostly files, the case where a stat
+# per directory entry dominates. Pass a second binary to compare against, e.g. a build of an
+# older revision:
+#   ./tests/bench_walk.sh [BASELINE_BIN]
+# Tree size and repetitions can be changed with BENCH_DIRS, BENCH_FILES and BENCH_RUNS.
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+REPO_ROOT="$(realpath "$SCRIPT_DIR/..")"
+BIN="${JCD_BIN:-$REPO_ROOT/target/release/jcd}"
+BASELINE="$1"
+
+DIRS="${BENCH_DIRS:-12}"    # Subdirectories per directory, three levels deep
+FILES="${BENCH_FILES:-200}" # Files per directory
+RUNS="${BENCH_RUNS:-5}"
+BENCH_ROOT="/tmp/jcd_bench_walk"
+
+for bin in "$BIN" ${BASELINE:+"$BASELINE"}; do
+    if [[ ! -x "$bin" ]]; then
+        echo "ERROR: jcd binary not found or not executable at: $bin"
+        exit 1
+    fi
+done
+
+echo "Generating $DIRS x $DIRS x $DIRS directories with $FILES files each..."
+rm -rf "$BENCH_ROOT"
+mkdir -p "$BENCH_ROOT"/{config,data,tree}
+(
+    cd "$BENCH_ROOT/tree" || exit 1
+    for a in $(seq "$DIRS"); do
+        for b in $(seq "$DIRS"); do
+            for c in $(seq "$DIRS"); do
+                dir="d$a/d$b/d$c"
+                mkdir -p "$dir"
+                (cd "$dir" && seq -f "file%g.txt" "$FILES" | xargs touch)
+            done
+        done
+    done
+)
+echo "$(find "$BENCH_ROOT/tree" | wc -l) entries"
+
+# Keep the user's config and visit history out of the way
+export XDG_CONFIG_HOME="$BENCH_ROOT/config"
+export XDG_DATA_HOME="$BENCH_ROOT/data"
+cd "$BENCH_ROOT/tree" || exit 1
+
+# Average wall time in milliseconds of a search that finds nothing, so the whole tree is read
+time_search() {
+    local bin="$1"
+    "$bin" no-such-directory --list --quiet >/dev/null 2>&1 # Warm the cache
+    local start end
+    start=$(date +%s%N)
+    for _ in $(seq "$RUNS"); do
+        "$bin" no-such-directory --list --quiet >/dev/null 2>&1
+    done
+    end=$(date +%s%N)
+    echo $(((end - start) / RUNS / 1000000))
+}
+
+current=$(time_search "$BIN")
+echo "jcd:      ${current} ms per search ($BIN)"
+if [[ -n "$BASELINE" ]]; then
+    baseline=$(time_search "$BASELINE")
+    echo "baseline: ${baseline} ms per search ($BASELINE)"
+    if ((current > 0)); then
+        echo "speedup:  $(awk "BEGIN { printf \"%.2f\", $baseline / $current }")x"
+    fi
+fi
+
+cd /
+rm -rf "$BENCH_ROOT"
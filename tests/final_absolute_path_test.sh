@@ -20,7 +20,7 @@ echo "AFTER:  '/uniquefoo' finds same result as 'uniquefoo'"
 echo
 
 echo "Testing relative pattern 'uniquefoo':"
-result_rel=$(../../../target/release/jcd uniquefoo 0 2>/dev/null)
+result_rel=$(../../../target/release/jcd uniquefoo --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Relative: $result_rel"
 else
@@ -28,7 +28,7 @@ else
 fi
 
 echo "Testing absolute pattern '/uniquefoo':"
-result_abs=$(../../../target/release/jcd /uniquefoo 0 2>/dev/null)
+result_abs=$(../../../target/release/jcd /uniquefoo --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Absolute: $result_abs"
     if [[ "$result_rel" == "$result_abs" ]]; then
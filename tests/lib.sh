@@ -0,0 +1,39 @@
+#!/bin/bash
+# Shared setup for the jcd test scripts. Source it near the top of a script:
+#
+#     source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+#
+# It defines the output colors, SCRIPT_DIR, REPO_ROOT and BIN (the jcd binary,
+# overridable with JCD_BIN), exits if the binary is missing, and provides
+# check() for comparing an expected value with the actual one.
+
+# Colors for output
+RED='\033[0;31m'
+GREEN='\033[0;32m'
+YELLOW='\033[1;33m'
+NC='\033[0m'
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+REPO_ROOT="$(realpath "$SCRIPT_DIR/..")"
+BIN="${JCD_BIN:-$REPO_ROOT/target/release/jcd}"
+
+if [[ ! -x "$BIN" ]]; then
+    echo "ERROR: jcd binary not found or not executable at: $BIN"
+    echo "Set JCD_BIN to override path. Example:"
+    echo "  JCD_BIN=/path/to/jcd ./tests/$(basename "$0")"
+    exit 1
+fi
+
+FAILED=0
+
+check() {
+    local description="$1"
+    local expected="$2"
+    local actual="$3"
+    if [[ "$actual" == "$expected" ]]; then
+        echo -e "${GREEN}✓ PASSED${NC}: $description"
+    else
+        echo -e "${RED}✗ FAILED${NC}: $description (expected '$expected', got '$actual')"
+        FAILED=1
+    fi
+}
@@ -64,7 +64,7 @@ test_result "Deep relative pattern '../../foo'" \
            ".*/foo$"
 
 test_result "Pattern matching '../ch' (first match)" \
-           "/datadrive/jcd/target/release/jcd '../ch' 0" \
+           "/datadrive/jcd/target/release/jcd '../ch' --index 0" \
            ".*/child[12]$"
 
 # Test absolute path functionality (new feature)
@@ -81,11 +81,11 @@ mkdir -p immediate_test/{un,unmemorize,unmemorize-demo}
 cd immediate_test
 
 test_result "Multiple immediate matches for 'un' (should return quickly)" \
-           "timeout 2s /datadrive/jcd/target/release/jcd 'un' 0" \
+           "timeout 2s /datadrive/jcd/target/release/jcd 'un' --index 0" \
            ".*/un$"
 
 test_result "Second match for 'un' pattern" \
-           "/datadrive/jcd/target/release/jcd 'un' 1" \
+           "/datadrive/jcd/target/release/jcd 'un' --index 1" \
            ".*/unmemorize"
 
 # Test shell function basic functionality
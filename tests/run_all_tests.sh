@@ -10,6 +10,10 @@ GREEN='\033[0;32m'
 YELLOW='\033[1;33m'
 NC='\033[0m' # No Color
 
+# Tests that source the wrapper record visits; keep them out of the user's frecency store
+export XDG_DATA_HOME="$(mktemp -d /tmp/jcd_test_data.XXXXXX)"
+trap 'rm -rf "$XDG_DATA_HOME"' EXIT
+
 TESTS_PASSED=0
 TESTS_FAILED=0
 FAILED_TESTS=()
@@ -76,6 +80,26 @@ run_test "Simple Functionality Test" "./simple_test.sh"
 run_test "Comprehensive Relative Path Test" "./test_relative_comprehensive.sh"
 run_test "Ignore Functionality Test" "./test_ignore_functionality.sh"
 run_test "Validation Test" "./validate_jcd.sh"
+run_test "List Mode Test" "./test_list_mode.sh"
+run_test "Output Formats Test" "./test_output_formats.sh"
+run_test "Shell Init Test" "./test_shell_init.sh"
+run_test "Frecency Test" "./test_frecency.sh"
+run_test "Bookmarks Test" "./test_bookmarks.sh"
+run_test "Fuzzy Matching Test" "./test_fuzzy.sh"
+run_test "Pattern Modes Test" "./test_pattern_modes.sh"
+run_test "Smart Case Test" "./test_smart_case.sh"
+run_test "Unicode Test" "./test_unicode.sh"
+run_test "Multi-Term Test" "./test_multi_term.sh"
+run_test "Acronym Test" "./test_acronym.sh"
+run_test "Typo Fallback Test" "./test_typo.sh"
+run_test "Ranking Config Test" "./test_ranking.sh"
+run_test "Explain Test" "./test_explain.sh"
+run_test "Parallel Walk Test" "./test_parallel_walk.sh"
+run_test "Symlink Test" "./test_symlinks.sh"
+run_test "Search Budget Test" "./test_budget.sh"
+run_test "Directory Index Test" "./test_index.sh"
+run_test "Background Indexer Test" "./test_daemon.sh"
+run_test "Gitignore Syntax Test" "./test_gitignore.sh"
 
 # Regression and bug fix tests
 run_test "Quick Regression Test" "./quick_regression_test.sh"
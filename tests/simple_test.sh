@@ -43,7 +43,7 @@ echo "Result: $result"
 # Test with patterns
 echo -e "\nTest 5: jcd '../ch' (should find child directories)"
 for i in {0..5}; do
-    result=$(/datadrive/jcd/target/release/jcd "../ch" $i 2>/dev/null)
+    result=$(/datadrive/jcd/target/release/jcd "../ch" --index $i 2>/dev/null)
     if [[ -n "$result" ]]; then
         echo "Match $i: $result"
     else
@@ -28,7 +28,7 @@ ls -la /datadrive2/ 2>/dev/null | grep -i un || echo "No directories found"
 
 echo
 echo "Testing jcd binary with '/datadrive2/un':"
-result=$(./target/release/jcd "/datadrive2/un" 0 2>/dev/null)
+result=$(./target/release/jcd "/datadrive2/un" --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "First match: $result"
 
@@ -49,7 +49,7 @@ fi
 echo
 echo "Getting all matches for '/datadrive2/un':"
 for i in {0..5}; do
-    result=$(./target/release/jcd "/datadrive2/un" $i 2>/dev/null)
+    result=$(./target/release/jcd "/datadrive2/un" --index $i 2>/dev/null)
     if [ $? -eq 0 ]; then
         basename_result=$(basename "$result")
         if [[ "$basename_result" =~ ^un ]]; then
@@ -29,7 +29,7 @@ echo "From directory: $(pwd)"
 echo
 
 echo "Relative pattern '4':"
-result_rel=$(../../../target/release/jcd 4 0 2>/dev/null)
+result_rel=$(../../../target/release/jcd 4 --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Found: $result_rel"
 else
@@ -37,7 +37,7 @@ else
 fi
 
 echo "Absolute pattern '/4':"
-result_abs=$(../../../target/release/jcd /4 0 2>/dev/null)
+result_abs=$(../../../target/release/jcd /4 --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Found: $result_abs"
     if [[ "$result_abs" == *"foo4"* ]]; then
@@ -56,7 +56,7 @@ echo "From directory: $(pwd)"
 echo
 
 echo "Relative pattern 'uniquefoo':"
-result_rel=$(../../../target/release/jcd uniquefoo 0 2>/dev/null)
+result_rel=$(../../../target/release/jcd uniquefoo --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Found: $result_rel"
 else
@@ -64,7 +64,7 @@ else
 fi
 
 echo "Absolute pattern '/uniquefoo':"
-result_abs=$(../../../target/release/jcd /uniquefoo 0 2>/dev/null)
+result_abs=$(../../../target/release/jcd /uniquefoo --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Found: $result_abs"
     if [[ "$result_rel" == "$result_abs" ]]; then
@@ -85,7 +85,7 @@ echo "From directory: $(pwd)"
 echo
 
 echo "Relative pattern 'test123':"
-result_rel=$(../../../target/release/jcd test123 0 2>/dev/null)
+result_rel=$(../../../target/release/jcd test123 --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Found: $result_rel"
 else
@@ -93,7 +93,7 @@ else
 fi
 
 echo "Absolute pattern '/test123':"
-result_abs=$(../../../target/release/jcd /test123 0 2>/dev/null)
+result_abs=$(../../../target/release/jcd /test123 --index 0 2>/dev/null)
 if [ $? -eq 0 ]; then
     echo "  ✓ Found: $result_abs"
     if [[ "$result_rel" == "$result_abs" ]]; then
@@ -0,0 +1,68 @@
+#!/bin/bash
+echo "Testing acronym and word-boundary matching (-w)..."
+
+TEST_ROOT="/tmp/jcd_test_acronym"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{foo-bar-service,web-api-gateway,my_long_name,com.example.app,api2go,DataBaseUtils,fbsx,xfbsx,plain}
+mkdir -p "$TEST_ROOT"/start/plain/deep/fooBarService
+mkdir -p "$TEST_ROOT"/{config/jcd,data}
+
+# Keep the user's config and visit history out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+quality() {
+    "$BIN" "$@" --json --quiet | sed 's/.*"match_quality":"\([A-Za-z]*\)".*/\1/'
+}
+
+spans() {
+    "$BIN" "$@" --json --quiet | sed 's/.*"match_spans":\(.*\)}/\1/'
+}
+
+# Acronyms are off by default
+check "acronyms are off by default" "" "$(list mln)"
+
+# Word segments start after separators, at digit boundaries and at camelCase humps
+check "-w splits at dashes" "web-api-gateway" "$(list -w wag)"
+check "-w splits at underscores" "my_long_name" "$(list -w mln)"
+check "-w splits at dots" "com.example.app" "$(list -w cea)"
+check "-w splits at digit boundaries" "api2go" "$(list -w a2g)"
+check "-w splits at camelCase humps" "DataBaseUtils" "$(list -w dbu)"
+check "--acronym is an alias for -w" "my_long_name" "$(list --acronym mln)"
+check "--no-acronym turns it off again" "" "$(list -w --no-acronym mln)"
+check "initials must appear in order" "" "$(list -w nlm)"
+
+# Prefix matches first, then acronyms, then substrings
+check "acronyms rank between prefix and substring matches" \
+    "fbsx foo-bar-service xfbsx" "$(list -w fbs)"
+check "acronym matches report their quality" "AcronymDown" "$(quality -w mln)"
+check "acronyms have their own bucket" '"priority":3' \
+    "$("$BIN" -w mln --json --quiet | grep -o '"priority":[0-9]*')"
+
+# A light visit history does not lift a substring match over an acronym in the next bucket
+mkdir -p "$TEST_ROOT/data/jcd"
+printf '1\t%s\t%s\n' "$(( $(date +%s) - 7200 ))" "$TEST_ROOT/start/xfbsx" > "$TEST_ROOT/data/jcd/visits"
+check "a visited substring match stays below acronyms" \
+    "fbsx foo-bar-service xfbsx" "$(list -w fbs)"
+echo "rank.acronym-child = 5" > "$TEST_ROOT/config/jcd/config"
+check "rank.acronym-child moves acronyms below substrings" \
+    "fbsx xfbsx foo-bar-service" "$(list -w fbs)"
+: > "$TEST_ROOT/config/jcd/config"
+check "spans cover each matched initial" "[[0,1],[3,4],[8,9]]" "$(spans -w mln)"
+
+# Acronyms work up the tree and in path patterns
+check "acronyms match up the tree" "AcronymUp" "$(cd plain/deep/fooBarService && quality -w jta)"
+check "acronyms work in path patterns" "plain/deep/fooBarService" "$(list -w plain/fbs)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,67 @@
+#!/bin/bash
+echo "Testing named bookmarks (--mark, --marks, --unmark, @name patterns)..."
+
+TEST_ROOT="/tmp/jcd_test_bookmarks"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/releases/v2.1/{src/server,docs} "$TEST_ROOT"/deployments "$TEST_ROOT"/elsewhere
+
+# Keep the test away from the real bookmarks
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+
+cd "$TEST_ROOT/releases/v2.1" && "$BIN" --mark release
+cd "$TEST_ROOT/deployments" && "$BIN" --mark deploy
+cd "$TEST_ROOT/elsewhere"
+
+check "bookmark file lives next to the ignore file" "2" "$(wc -l < "$XDG_CONFIG_HOME/jcd/bookmarks" | tr -d ' ')"
+check "@name jumps to the bookmark" "$TEST_ROOT/releases/v2.1" "$("$BIN" @release --quiet)"
+check "@name/sub searches below the bookmark" "$TEST_ROOT/releases/v2.1/src/server" "$("$BIN" @release/serv --quiet)"
+check "@name/sub/path follows path patterns" "$TEST_ROOT/releases/v2.1/src/server" "$("$BIN" @release/src/ser --quiet)"
+check "@prefix completes bookmark names" "$TEST_ROOT/deployments" "$("$BIN" @dep --quiet)"
+check "--marks lists bookmarks" "@deploy   $TEST_ROOT/deployments" "$("$BIN" --marks | head -1)"
+
+# A hand-edited file, out of order, with a prefix of another bookmark's name
+printf 'zeta\t%s\ndeploy-old\t%s\n' "$TEST_ROOT/elsewhere" "$TEST_ROOT/releases" >> "$XDG_CONFIG_HOME/jcd/bookmarks"
+check "--marks sorts a hand-edited file by name" "@deploy @deploy-old @release @zeta" \
+    "$("$BIN" --marks | cut -d' ' -f1 | tr '\n' ' ' | sed 's/ $//')"
+check "@prefix ranks an exact name first" "$TEST_ROOT/deployments $TEST_ROOT/releases" \
+    "$("$BIN" -i @DEPLOY --list --quiet | tr '\n' ' ' | sed 's/ $//')"
+check "@prefix follows the case policy" "" "$("$BIN" -S @DEP --list --quiet 2>/dev/null)"
+check "@name/sub does not search above the bookmark" "" "$("$BIN" @release/bookmarks --list --quiet 2>/dev/null)"
+"$BIN" --unmark zeta && "$BIN" --unmark deploy-old
+
+# Re-marking replaces the old target
+cd "$TEST_ROOT/elsewhere" && "$BIN" --mark deploy
+check "re-marking replaces the target" "$TEST_ROOT/elsewhere" "$("$BIN" @deploy --quiet)"
+
+"$BIN" --unmark deploy
+check "--unmark deletes the bookmark" "1" "$(wc -l < "$XDG_CONFIG_HOME/jcd/bookmarks" | tr -d ' ')"
+
+if ! "$BIN" @deploy --quiet 2>/dev/null && ! "$BIN" --unmark deploy 2>/dev/null; then
+    echo -e "${GREEN}✓ PASSED${NC}: unknown bookmarks are reported as errors"
+else
+    echo -e "${RED}✗ FAILED${NC}: unknown bookmarks should fail"
+    FAILED=1
+fi
+
+if ! "$BIN" --mark "bad/name" 2>/dev/null; then
+    echo -e "${GREEN}✓ PASSED${NC}: names with separators are rejected"
+else
+    echo -e "${RED}✗ FAILED${NC}: 'bad/name' should be rejected"
+    FAILED=1
+fi
+
+# The bash wrapper passes bookmark commands through and jumps to @name
+export JCD_BINARY="$BIN"
+source "$REPO_ROOT/jcd_function.sh"
+cd "$TEST_ROOT/releases/v2.1/docs" && jcd --mark docs
+cd "$TEST_ROOT/elsewhere"
+jcd @docs >/dev/null
+check "wrapper jumps to a bookmark" "$TEST_ROOT/releases/v2.1/docs" "$PWD"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,74 @@
+#!/bin/bash
+echo "Testing search budgets (--max-* / JCD_MAX_* / search.* config)..."
+
+TEST_ROOT="/tmp/jcd_test_budget"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+START="$TEST_ROOT/ancestor/outer/start"
+mkdir -p "$TEST_ROOT"/{config/jcd,data} "$START"/{a/b/c/needle,x/needle-near,p1/p2/p3/proj/src}
+
+# Keep the user's config, ignore file and visit history out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+
+cd "$START"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$START/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+warning() {
+    "$BIN" "$@" --list 2>&1 >/dev/null
+}
+
+# Depth budgets shape the search
+check "the default depth reaches four levels down" "a/b/c/needle x/needle-near" "$(list needle)"
+check "--max-depth stops the down-tree search" "x/needle-near" "$(list --max-depth 2 needle)"
+check "JCD_MAX_DEPTH sets the depth" "x/needle-near" "$(JCD_MAX_DEPTH=2 list needle)"
+echo "search.depth = 2" > "$XDG_CONFIG_HOME/jcd/config"
+check "search.depth sets the depth" "x/needle-near" "$(list needle)"
+check "flags override the config file" "a/b/c/needle x/needle-near" "$(list --max-depth 4 needle)"
+rm "$XDG_CONFIG_HOME/jcd/config"
+check "multi-term searches respect the depth" "x/needle-near" "$(list --max-depth 3 c needle)"
+check "ancestors are found by default" "$TEST_ROOT/ancestor" "$(list ancestor)"
+check "--max-up limits the walk up the tree" "" "$(list --max-up 1 ancestor)"
+check "path patterns search four levels for their first part" "p1/p2/p3/proj" "$(list p3/proj)"
+check "--max-path-depth limits path patterns" "" "$(list --max-path-depth 2 p3/proj)"
+
+# Result, directory and time budgets cut the search short, and jcd says so
+check "--max-results keeps the first matches" "x/needle-near" "$(list --max-results 1 needle)"
+check "running out of results is reported" \
+    "jcd: search hit the result limit (1), results may be incomplete (see --max-results)" \
+    "$(warning --max-results 1 needle)"
+check "running out of directories is reported" \
+    "jcd: search hit the directory limit (2), results may be incomplete (see --max-dirs)" \
+    "$(warning --max-dirs 2 needle)"
+check "running out of time is reported" \
+    "jcd: search hit the time limit (0 ms), results may be incomplete (see --max-time)" \
+    "$(JCD_MAX_TIME=0 warning needle)"
+check "a search within budget prints nothing" "" "$(warning needle)"
+check "--quiet hides the warning" "" "$("$BIN" --max-dirs 2 needle --list --quiet 2>&1 >/dev/null)"
+check "none lifts a budget again" "a/b/c/needle x/needle-near" "$(JCD_MAX_DIRS=2 list --max-dirs none needle)"
+mkdir -p many/hay{01..25}
+check "twenty results are kept by default" "20" "$("$BIN" hay --list --quiet | wc -l)"
+check "the default result limit is reported" \
+    "jcd: search hit the result limit (20), results may be incomplete (see --max-results)" \
+    "$(warning hay)"
+check "none lifts the default result limit" "25" "$("$BIN" hay --max-results none --list --quiet | wc -l)"
+check "a larger limit raises it" "25" "$(JCD_MAX_RESULTS=30 "$BIN" hay --list --quiet | wc -l)"
+rm -rf many
+check "--explain names the budget" "$START/x: search stopped here, directory budget used up" \
+    "$("$BIN" --max-dirs 2 --explain needle | grep -o "$START/x: .*")"
+
+# Invalid values are errors
+check "non-numeric budgets are rejected" "Error: Invalid value 'many' for --max-results" \
+    "$("$BIN" --max-results many needle 2>&1)"
+check "depth cannot be none" "Error: Invalid value 'none' for --max-depth" \
+    "$("$BIN" --max-depth none needle 2>&1)"
+check "a missing value is rejected" "Error: --max-dirs expects a number" "$("$BIN" needle --max-dirs 2>&1)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
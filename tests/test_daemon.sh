@@ -0,0 +1,113 @@
+#!/bin/bash
+echo "Testing the background indexer (jcd daemon watch)..."
+
+TEST_ROOT="/tmp/jcd_test_daemon"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+if [[ "$(uname)" != "Linux" ]]; then
+    echo "Skipping: the daemon needs inotify"
+    exit 0
+fi
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/{cache,config/jcd,data,run}
+mkdir -p "$TEST_ROOT"/start/{src/app/models/needle,docs/guide,node_modules/pkg/needle,tools/needle-cli}
+
+# Keep the user's config, ignore file, visit history, indexes and daemon out of the way
+export XDG_CACHE_HOME="$TEST_ROOT/cache"
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+export XDG_RUNTIME_DIR="$TEST_ROOT/run"
+echo '^node_modules$' > "$XDG_CONFIG_HOME/jcd/ignore"
+
+cd "$TEST_ROOT/start"
+
+DAEMON=
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+# Whether a search asked the daemon for its listings
+uses_daemon() {
+    JCD_DEBUG=1 "$BIN" "$@" --list --quiet 2>&1 >/dev/null | grep -c "Listing directories from the daemon"
+}
+
+# Start the daemon in the background and wait for its socket
+start_daemon() {
+    "$BIN" daemon watch "$@" > "$TEST_ROOT/daemon.log" 2>&1 &
+    DAEMON=$!
+    for _ in $(seq 50); do
+        [[ -S "$XDG_RUNTIME_DIR/jcd.sock" ]] && grep -q Listening "$TEST_ROOT/daemon.log" && return
+        sleep 0.1
+    done
+}
+
+stop_daemon() {
+    [[ -n "$DAEMON" ]] && kill "$DAEMON" 2>/dev/null && wait "$DAEMON" 2>/dev/null
+    DAEMON=
+}
+trap stop_daemon EXIT
+
+# Without a daemon every search reads from disk
+walked=$(list needle)
+check "searches work without a daemon" "src/app/models/needle tools/needle-cli" "$walked"
+check "no daemon is used when none is running" "0" "$(uses_daemon needle)"
+check "watching needs a root" "Error: Nothing to watch: name directories or set daemon.root in the config" \
+    "$("$BIN" daemon watch 2>&1)"
+check "watching needs XDG_RUNTIME_DIR" "1" \
+    "$(env -u XDG_RUNTIME_DIR "$BIN" daemon watch . >/dev/null 2>&1; echo $?)"
+
+start_daemon "$TEST_ROOT/start"
+check "the daemon skips ignored directories" "Watching 9 directories below $TEST_ROOT/start
+Listening on $XDG_RUNTIME_DIR/jcd.sock" "$(cat "$TEST_ROOT/daemon.log")"
+check "a second daemon is refused" "Error: A jcd daemon is already listening on $XDG_RUNTIME_DIR/jcd.sock" \
+    "$("$BIN" daemon watch . 2>&1)"
+
+# The daemon only saves reads: every search answers exactly as a walk would
+check "searches ask the daemon" "1" "$(uses_daemon needle)"
+check "daemon searches match walked ones" "$(list --no-index needle)" "$(list needle)"
+check "daemon searches from a subdirectory match walked ones" \
+    "$(cd src && list --no-index models)" "$(cd src && list models)"
+check "path patterns use the daemon too" "1" "$(uses_daemon app/needle)"
+check "daemon path pattern searches match walked ones" "$(list --no-index app/needle)" "$(list app/needle)"
+check "multi-term searches use the daemon too" "$(list --no-index app needle)" "$(list app needle)"
+check "ignored directories are read from disk with -x" "$(list -x --no-index pkg)" "$(list -x pkg)"
+check "--no-index skips the daemon" "0" "$(uses_daemon --no-index needle)"
+
+# Changes reach the daemon through inotify
+mkdir -p src/app/views/needle-new/deep/needle-deep
+rm -rf tools/needle-cli
+sleep 0.3
+check "new and removed directories are seen" \
+    "src/app/models/needle src/app/views/needle-new src/app/views/needle-new/deep/needle-deep" "$(list needle)"
+check "new subtrees are watched" "src/app/views/needle-new/deep/needle-deep" "$(list needle-deep)"
+mv src/app/views docs/views
+sleep 0.3
+check "renamed directories are seen" "$(list --no-index needle)" "$(list needle)"
+mkdir tools/needle-cli && ln -s "$TEST_ROOT/start/tools/needle-cli" docs/needle-link
+sleep 0.3
+check "symlinks to directories are seen" "$(list --no-index needle)" "$(list needle)"
+
+# The wrapper hands daemon commands to the binary instead of jumping
+result=$(JCD_BINARY="$BIN" bash -c 'source "$0" >/dev/null 2>&1; jcd daemon watch . 2>&1 | sed "s|:.*||"; pwd' "$REPO_ROOT/jcd_function.sh")
+check "the wrapper runs daemon commands and stays put" "Error
+$TEST_ROOT/start" "$result"
+
+# A daemon that is gone leaves its socket behind; searches read from disk again
+kill -9 "$DAEMON" && wait "$DAEMON" 2>/dev/null
+DAEMON=
+check "searches fall back to disk when the daemon is gone" "$(list --no-index needle)" "$(list needle)"
+
+# Roots can come from the config, and a stale socket is replaced
+echo "daemon.root = $TEST_ROOT/start/docs" > "$XDG_CONFIG_HOME/jcd/config"
+start_daemon
+check "configured roots are watched" "Watching 6 directories below $TEST_ROOT/start/docs" \
+    "$(head -n 1 "$TEST_ROOT/daemon.log")"
+check "searches outside the roots read from disk" "$(list --no-index needle)" "$(list needle)"
+stop_daemon
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
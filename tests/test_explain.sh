@@ -0,0 +1,70 @@
+#!/bin/bash
+echo "Testing --explain reports..."
+
+TEST_ROOT="/tmp/jcd_test_explain"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{api,api-gateway,node_modules/api,deep/1/2/3/4/5/6/7/8/logs,other/logs-old}
+mkdir -p "$TEST_ROOT"/{config/jcd,data}
+echo '^node_modules$' > "$TEST_ROOT/config/jcd/ignore"
+
+# Keep the user's config, ignore file and visit history out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+
+cd "$TEST_ROOT/start"
+
+contains() {
+    local description="$1"
+    local expected="$2"
+    local report="$3"
+    if grep -qF -- "$expected" <<< "$report"; then
+        echo -e "${GREEN}✓ PASSED${NC}: $description"
+    else
+        echo -e "${RED}✗ FAILED${NC}: $description (no '$expected' in report)"
+        echo "$report"
+        FAILED=1
+    fi
+}
+
+# Candidates come in ranked order with their quality, depth, bucket and tie-break keys
+report=$("$BIN" --explain api)
+check "the report starts with the query" "Candidates for 'api', best first:" "$(head -1 <<< "$report")"
+contains "candidates are numbered like --index" "  0. $TEST_ROOT/start/api" "$report"
+contains "the second candidate follows" "  1. $TEST_ROOT/start/api-gateway" "$report"
+contains "quality, depth and bucket are shown" "PrefixDown, depth 1, bucket 2" "$report"
+contains "tie-break keys are shown" "tie-breaks: frecency=0.000 priority=0 quality=ExactDown spread=0 score=1 depth=1" "$report"
+
+# Dropped directories name their reason
+contains "ignored directories name the pattern and file" \
+    "$TEST_ROOT/start/node_modules: ignored by '^node_modules\$' from $TEST_ROOT/config/jcd/ignore" "$report"
+contains "an early stop is reported" "$TEST_ROOT/start: searched one level down only, a closer match was found" "$report"
+report=$("$BIN" --explain logs)
+contains "the depth limit is reported" "$TEST_ROOT/start/deep/1/2/3/4/5/6/7: not searched below, at the depth limit" "$report"
+contains "matches within the limit are candidates" "  0. $TEST_ROOT/start/other/logs-old" "$report"
+
+# Visit history beyond the depth limit
+"$BIN" --record "$TEST_ROOT/start/deep/1/2/3/4/5/6/7/8/logs"
+contains "visited directories past the limit are reported" \
+    "$TEST_ROOT/start/deep/1/2/3/4/5/6/7/8/logs: past the depth limit" "$("$BIN" --explain logs)"
+
+# Tie-break keys follow the configured order, and visit history lifts are explained
+echo "rank.tiebreak = depth, score" > "$TEST_ROOT/config/jcd/config"
+contains "tie-break keys follow rank.tiebreak" "tie-breaks: depth=1 score=1 frecency=0.000" "$("$BIN" --explain api)"
+
+# No candidates still prints a report, with a failing status
+report=$("$BIN" --explain nothing-here-at-all)
+status=$?
+contains "empty results say so" "  (none)" "$report"
+check "empty results exit with 1" "1" "$status"
+
+# The wrapper hands --explain to the binary instead of jumping
+result=$(cd "$TEST_ROOT/start" && JCD_BINARY="$BIN" bash -c 'source "$0" >/dev/null 2>&1; jcd --explain api | head -1; pwd' "$REPO_ROOT/jcd_function.sh")
+check "the wrapper prints the report and stays put" "Candidates for 'api', best first:
+$TEST_ROOT/start" "$result"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,71 @@
+#!/bin/bash
+echo "Testing frecency visit store (recording, ranking, pruning)..."
+
+TEST_ROOT="/tmp/jcd_test_frecency"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{api,services/backend/api,gone}
+mkdir -p "$TEST_ROOT/data"
+
+# Keep the test away from the real visit store
+export XDG_DATA_HOME="$TEST_ROOT/data"
+STORE="$XDG_DATA_HOME/jcd/visits"
+
+cd "$TEST_ROOT/start"
+
+# Without history the closest match wins
+check "closest match wins without history" "$TEST_ROOT/start/api" "$("$BIN" api --quiet)"
+
+# Frequent visits promote the deeper directory
+for _ in $(seq 1 20); do
+    "$BIN" --record services/backend/api
+done
+check "visit is recorded in the store" "1" "$(grep -c "services/backend/api$" "$STORE")"
+check "frequently visited match ranks first" "$TEST_ROOT/start/services/backend/api" "$("$BIN" api --quiet)"
+check "closest match is still offered" "$TEST_ROOT/start/api" "$("$BIN" api --index 1 --quiet)"
+
+# Directories that no longer exist are only pruned when the store ages
+"$BIN" --record gone
+rmdir gone
+"$BIN" --record api
+check "an ordinary visit keeps removed directories" "1" "$(grep -c "/gone$" "$STORE")"
+# A copy of the store with enough visits to age on the next one
+AGED="$TEST_ROOT/aged"
+mkdir -p "$AGED/jcd"
+cp "$STORE" "$AGED/jcd/visits"
+printf '20000\t%s\t%s\n' "$(date +%s)" "$TEST_ROOT/start/services" >> "$AGED/jcd/visits"
+XDG_DATA_HOME="$AGED" "$BIN" --record api
+check "removed directory is pruned when the store ages" "0" "$(grep -c "/gone$" "$AGED/jcd/visits")"
+check "aging keeps directories that exist" "1" "$(grep -c "start/services$" "$AGED/jcd/visits")"
+
+# Visited directories outside the current tree are reachable by name
+mkdir -p "$TEST_ROOT"/work/{a/servlet,b/service}
+"$BIN" --record "$TEST_ROOT/work/b/service"
+cd "$TEST_ROOT/work/a"
+check "history hit when the tree has no match" "$TEST_ROOT/work/b/service" "$("$BIN" service --quiet)"
+check "history hit is labeled" "History" "$("$BIN" service --json --quiet | sed 's/.*"match_quality":"\([A-Za-z]*\)".*/\1/')"
+check "history hit after weak tree matches" "$TEST_ROOT/work/b/service" "$("$BIN" rvice --list --quiet | tail -1)"
+check "good tree match suppresses history" "$TEST_ROOT/work/a/servlet" "$("$BIN" serv --list --quiet)"
+cd "$TEST_ROOT/start"
+
+if ! "$BIN" --record "$TEST_ROOT/no/such/dir" 2>/dev/null; then
+    echo -e "${GREEN}✓ PASSED${NC}: recording a missing directory fails"
+else
+    echo -e "${RED}✗ FAILED${NC}: recording a missing directory should fail"
+    FAILED=1
+fi
+
+# The bash wrapper records after each successful jump
+export JCD_BINARY="$BIN"
+source "$REPO_ROOT/jcd_function.sh"
+jcd backend >/dev/null
+check "wrapper records the jump" "1" "$(grep -c "services/backend$" "$STORE")"
+cd "$TEST_ROOT/start"
+JCD_RECORD=0 jcd api/ >/dev/null
+check "JCD_RECORD=0 skips recording" "1" "$(grep "start/api$" "$STORE" | cut -f1)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,39 @@
+#!/bin/bash
+echo "Testing fuzzy subsequence matching (-f / --fuzzy)..."
+
+TEST_ROOT="/tmp/jcd_test_fuzzy"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{messaging-service,mgmt-svc,misc,tools/msgService,svc-mg}
+mkdir -p "$TEST_ROOT"/my-great-service/inner
+mkdir -p "$TEST_ROOT"/projects/web-app/{src,dist}
+
+cd "$TEST_ROOT/start"
+
+check "fuzzy matching is off by default" "" "$("$BIN" mgsvc --quiet 2>/dev/null)"
+check "-f finds a subsequence match" "$TEST_ROOT/start/mgmt-svc" "$("$BIN" -f mgsvc --quiet)"
+check "--fuzzy is an alias for -f" "$TEST_ROOT/start/mgmt-svc" "$("$BIN" --fuzzy mgsvc --quiet)"
+check "--no-fuzzy turns it off again" "" "$("$BIN" -f --no-fuzzy mgsvc --quiet 2>/dev/null)"
+check "word boundary runs outrank scattered matches" \
+    "$TEST_ROOT/start/mgmt-svc $TEST_ROOT/start/messaging-service" \
+    "$("$BIN" -f -S mgsvc --list --quiet | tr '\n' ' ' | sed 's/ $//')"
+check "fuzzy honors -S" "$TEST_ROOT/start/messaging-service" "$("$BIN" -f -S msgsvc --list --quiet)"
+check "fuzzy ignores case for lowercase patterns" "$TEST_ROOT/start/tools/msgService" "$("$BIN" -f msgsvc --list --quiet | head -1)"
+check "fuzzy honors -i" "$TEST_ROOT/start/tools/msgService" "$("$BIN" -f -i msgSvc --list --quiet | head -1)"
+check "fuzzy ranks below substring matches" "$TEST_ROOT/start/svc-mg" "$("$BIN" -f svc --list --quiet | head -1)"
+check "fuzzy matches are labeled" "Fuzzy" \
+    "$("$BIN" -f mgsvc --json --quiet | sed 's/.*"match_quality":"\([A-Za-z]*\)".*/\1/')"
+check "fuzzy spans cover each matched run" '[[0,2],[5,8]]' \
+    "$("$BIN" -f mgsvc --json --quiet | sed 's/.*"match_spans":\(.*\)}/\1/')"
+
+cd "$TEST_ROOT/my-great-service/inner"
+check "fuzzy matches up the tree" "$TEST_ROOT/my-great-service" "$("$BIN" -f mgsvc --quiet)"
+
+cd "$TEST_ROOT"
+check "fuzzy matches in path patterns" "$TEST_ROOT/projects/web-app/src" "$("$BIN" -f prj/wbap/src --quiet)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,93 @@
+#!/bin/bash
+echo "Testing gitignore-syntax ignore files..."
+
+TEST_ROOT="/tmp/jcd_test_gitignore"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/{cache,config/jcd,data,run}
+mkdir -p "$TEST_ROOT"/start/{build,rebuild-tools,src/build,src/keep-build,web/dist,docs/dist}
+mkdir -p "$TEST_ROOT"/start/{vendor/lib/deep,node_modules/pkg,.git/hooks,cache1,cache22}
+
+# Keep the user's config, ignore file, visit history and index out of the way
+export XDG_CACHE_HOME="$TEST_ROOT/cache"
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+export XDG_RUNTIME_DIR="$TEST_ROOT/run"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+# The regex format matches bare names, so `build` also hides rebuild-tools
+echo 'build' > .jcdignore
+check "regex patterns match anywhere in the name" "" "$(list build)"
+
+# A header switches the file to gitignore rules
+cat > .jcdignore <<'RULES'
+# Project ignores
+#syntax: gitignore
+build/
+/docs/dist
+**/lib/**
+.git
+node_modules
+cache?
+!src/build
+RULES
+check "rules match whole names" "rebuild-tools src/build src/keep-build" "$(list build)"
+check "a leading / anchors at the search root" "web/dist" "$(list dist)"
+check "** spans directories" "vendor/lib" "$(list lib)"
+check "everything inside a ** rule is ignored" "" "$(list deep)"
+check "ignored directories are not searched below" "" "$(list hooks)"
+check "? matches one character" "cache22" "$(list cache)"
+check "path patterns skip ignored directories" "" "$(list .git/hooks)"
+check "-x bypasses gitignore rules" "build rebuild-tools" "$(list -x build)"
+check "-x lets path patterns into ignored directories" ".git/hooks" "$(list -x .git/hooks)"
+check "--explain names the rule" "1" \
+    "$("$BIN" dist --explain 2>&1 | grep -c "docs/dist: ignored by '/docs/dist'")"
+
+# The file name chooses the syntax too, and a header can switch back to regex
+rm .jcdignore
+printf 'build/\n' > .jcdgitignore
+check ".jcdgitignore uses gitignore rules" "rebuild-tools" "$(list rebuild)"
+check ".jcdgitignore rules match at any depth" "rebuild-tools src/keep-build" "$(list build)"
+printf '#syntax: regex\nbuild\n' > .jcdgitignore
+check "#syntax: regex keeps the regex format" "" "$(list build)"
+rm .jcdgitignore
+
+printf '/web\n' > "$XDG_CONFIG_HOME/jcd/gitignore"
+check "the user config directory holds a gitignore file" "docs/dist" "$(list dist)"
+printf 'dist\n' > "$XDG_CONFIG_HOME/jcd/ignore"
+check "the regex file wins over the gitignore file beside it" "" "$(list dist)"
+rm "$XDG_CONFIG_HOME/jcd/ignore"
+
+# Anchored rules follow the directory the search starts from
+printf '/dist\n' > "$XDG_CONFIG_HOME/jcd/gitignore"
+check "anchored rules skip deeper matches" "docs/dist web/dist" "$(list dist)"
+check "anchored rules are relative to where the search starts" "" "$(cd docs && list dist)"
+
+# Anchored rules also hold for visited directories, the index and the daemon
+printf '/web\n' > "$XDG_CONFIG_HOME/jcd/gitignore"
+"$BIN" --record "$TEST_ROOT/start/web/dist"
+check "visited directories below an anchored rule stay ignored" "docs/dist" "$(list dist)"
+check "the index skips anchored rules" "Indexed 17 directories below $TEST_ROOT/start" "$("$BIN" index build)"
+"$BIN" index clear >/dev/null
+if [[ "$(uname)" == "Linux" ]]; then
+    "$BIN" daemon watch "$TEST_ROOT/start" > "$TEST_ROOT/daemon.log" 2>&1 &
+    DAEMON=$!
+    for _ in $(seq 50); do
+        grep -q Listening "$TEST_ROOT/daemon.log" && break
+        sleep 0.1
+    done
+    check "the daemon skips anchored rules" "Watching 17 directories below $TEST_ROOT/start" \
+        "$(head -n 1 "$TEST_ROOT/daemon.log")"
+    kill "$DAEMON" && wait "$DAEMON" 2>/dev/null
+fi
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -1,23 +1,8 @@
 #!/bin/bash
 echo "Testing .jcdignore pattern limits and regex safety..."
 
-# Colors for output
-RED='\033[0;31m'
-GREEN='\033[0;32m'
-YELLOW='\033[1;33m'
-NC='\033[0m'
-
 TEST_ROOT="/tmp/jcd_test_ignore"
-SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
-REPO_ROOT="$(realpath "$SCRIPT_DIR/..")"
-BIN="${JCD_BIN:-$REPO_ROOT/target/release/jcd}"
-
-if [[ ! -x "$BIN" ]]; then
-    echo "ERROR: jcd binary not found or not executable at: $BIN"
-    echo "Set JCD_BIN to override path. Example:"
-    echo "  JCD_BIN=/path/to/jcd ./tests/test_ignore_patterns.sh"
-    exit 1
-fi
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
 
 rm -rf "$TEST_ROOT"
 mkdir -p "$TEST_ROOT/matchthis"
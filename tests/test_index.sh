@@ -0,0 +1,82 @@
+#!/bin/bash
+echo "Testing the directory index (jcd index build/status/clear, --no-index)..."
+
+TEST_ROOT="/tmp/jcd_test_index"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/{cache,config/jcd,data}
+mkdir -p "$TEST_ROOT"/start/{src/app/models/needle,docs/guide,node_modules/pkg/needle,tools/needle-cli}
+
+# Keep the user's config, ignore file, visit history and indexes out of the way
+export XDG_CACHE_HOME="$TEST_ROOT/cache"
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+echo '^node_modules$' > "$XDG_CONFIG_HOME/jcd/ignore"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+status() {
+    "$BIN" index status | sed "s|$TEST_ROOT/start|ROOT|; s|built [0-9]*[smhd] ago|built recently|"
+}
+
+check "status without an index" "No directory indexes" "$("$BIN" index status)"
+check "build counts the directories outside ignored ones" \
+    "Indexed 9 directories below $TEST_ROOT/start" "$("$BIN" index build)"
+check "the index is kept in the cache directory" "1" "$(ls "$XDG_CACHE_HOME/jcd/index" | wc -l | tr -d ' ')"
+check "status lists the index" "ROOT: 9 directories, built recently, 0 changed since" "$(status)"
+
+# The index only saves reads: every search answers exactly as a walk would
+walked=$(list --no-index needle)
+check "indexed searches match walked ones" "$walked" "$(list needle)"
+check "indexed searches from a subdirectory match walked ones" \
+    "$(cd src && list --no-index models)" "$(cd src && list models)"
+check "multi-term searches use the index too" "$(list --no-index app needle)" "$(list app needle)"
+check "ignored directories are read on demand with -x" "$(list -x --no-index pkg)" "$(list -x pkg)"
+
+# Changes are picked up from directory mtimes; the -x search above added node_modules
+mkdir -p src/app/views/needle-new
+rm -rf tools/needle-cli
+check "status counts the changed directories" "ROOT: 12 directories, built recently, 3 changed since" "$(status)"
+check "new and removed directories are found after a change" "$(list --no-index needle)" "$(list needle)"
+check "the refreshed index is saved" "ROOT: 13 directories, built recently, 0 changed since" "$(status)"
+check "--no-index leaves the index alone" "ROOT: 13 directories, built recently, 1 changed since" \
+    "$(mkdir src/extra && list --no-index extra >/dev/null && rmdir src/extra && status)"
+
+# The wrapper hands index commands to the binary instead of jumping
+result=$(JCD_BINARY="$BIN" bash -c 'source "$0" >/dev/null 2>&1; jcd index status | sed "s|:.*||"; pwd' "$REPO_ROOT/jcd_function.sh")
+check "the wrapper runs index commands and stays put" "$TEST_ROOT/start
+$TEST_ROOT/start" "$result"
+
+# Clearing
+check "clear names the root" "Removed the index of $TEST_ROOT/start" "$("$BIN" index clear "$TEST_ROOT/start")"
+check "clearing a missing index is an error" "Error: No index of $TEST_ROOT/start" \
+    "$("$BIN" index clear "$TEST_ROOT/start" 2>&1)"
+"$BIN" index build >/dev/null
+(cd docs && "$BIN" index build >/dev/null)
+check "clear without a directory removes every index" "Removed 2 directory indexes" "$("$BIN" index clear)"
+check "searches work without any index" "src/app/models/needle src/app/views/needle-new" "$(list needle)"
+check "a missing directory cannot be indexed" "1" "$("$BIN" index build "$TEST_ROOT/missing" 2>/dev/null; echo $?)"
+
+# The index is queried instead of walked, so the depth and time budgets do not hide deep targets
+DEEP="$TEST_ROOT/deep/a/b/c/d/e/f/g/h/i/j"
+mkdir -p "$DEEP/deep-target" "$DEEP/deep-other"
+cd "$TEST_ROOT/deep"
+check "a walk does not reach below the depth budget" "" "$("$BIN" --no-index deep-target --quiet)"
+"$BIN" index build >/dev/null
+check "the index finds targets below the depth budget" "$DEEP/deep-target" "$("$BIN" deep-target --quiet)"
+check "the index finds them with no depth or time left" "$DEEP/deep-target" \
+    "$("$BIN" --max-depth 1 --max-time 0 deep-target --quiet)"
+rm -rf "$DEEP/deep-target"
+mkdir "$DEEP/deep-target-new"
+check "matches are checked against their parent's modification time" "$DEEP/deep-target-new" \
+    "$("$BIN" deep-target --quiet)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
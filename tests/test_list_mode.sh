@@ -0,0 +1,49 @@
+#!/bin/bash
+echo "Testing --list mode (single invocation returns every ranked match)..."
+
+TEST_ROOT="/tmp/jcd_test_list"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{listdir,listdir2,other/listdir3,other/deep/listdir4}
+
+cd "$TEST_ROOT/start"
+
+# Build the expected list the slow way, one index at a time
+expected=()
+idx=0
+while match=$("$BIN" istdir --index "$idx" --quiet 2>/dev/null) && [[ -n "$match" ]]; do
+    expected+=("$match")
+    idx=$((idx + 1))
+done
+
+actual=()
+while IFS= read -r line; do
+    actual+=("$line")
+done < <("$BIN" istdir --list --quiet)
+
+if [[ ${#actual[@]} -eq 4 ]] && [[ "${actual[*]}" == "${expected[*]}" ]]; then
+    echo -e "${GREEN}✓ PASSED${NC}: --list matches per-index output (${#actual[@]} matches)"
+else
+    echo -e "${RED}✗ FAILED${NC}: --list returned [${actual[*]}], expected [${expected[*]}]"
+    FAILED=1
+fi
+
+if [[ "${actual[0]}" == "$TEST_ROOT/start/listdir" ]]; then
+    echo -e "${GREEN}✓ PASSED${NC}: best match is listed first"
+else
+    echo -e "${RED}✗ FAILED${NC}: first listed match was '${actual[0]}'"
+    FAILED=1
+fi
+
+if ! "$BIN" nosuchdirectoryname --list --quiet >/dev/null 2>&1; then
+    echo -e "${GREEN}✓ PASSED${NC}: --list exits non-zero when nothing matches"
+else
+    echo -e "${RED}✗ FAILED${NC}: --list should fail when nothing matches"
+    FAILED=1
+fi
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,67 @@
+#!/bin/bash
+echo "Testing multi-term queries and --index..."
+
+TEST_ROOT="/tmp/jcd_test_multi_term"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/work/api/{v2,old/v2}
+mkdir -p "$TEST_ROOT"/start/work/v2/api
+mkdir -p "$TEST_ROOT"/start/play/api/v2
+mkdir -p "$TEST_ROOT"/start/release/2024
+mkdir -p "$TEST_ROOT"/start/logs/{2023,2024} "$TEST_ROOT"/start/{archive,archive-old}
+mkdir -p "$TEST_ROOT"/elsewhere/work/api/v3
+mkdir -p "$TEST_ROOT"/data
+
+# Keep the user's visit history out of the way
+export XDG_DATA_HOME="$TEST_ROOT/data"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+# Terms match path components in order, the last one the directory name
+check "every term must match in order" "start/work/api/v2 start/work/api/old/v2" "$(list work api v2)"
+check "the last term must match the final component" "start/work/v2/api" "$(list work v2 api)"
+check "terms may skip components, tighter matches first" "start/work/v2 start/work/api/v2 start/work/api/old/v2" "$(list work v2)"
+check "multi-term matches report their spread" '"spread":2' \
+    "$("$BIN" work api v2 --json --quiet | grep -o '"spread":[0-9]*')"
+check "terms follow the case policy" "start/work/api/v2 start/work/api/old/v2" "$(list WORK Api v2 -i)"
+check "terms work with globs" "start/play/api/v2 start/work/api/v2 start/work/api/old/v2" "$(list 'a*' 'v?')"
+check "a missing term matches nothing" "" "$(list work nothing v2)"
+
+# Ancestors of the current directory count as path components
+cd "$TEST_ROOT/start/work/api"
+check "earlier terms can match above the current directory" "start/work/api/v2 start/work/api/old/v2" "$(list work v2)"
+check "matches up the tree" "start/work" "$(cd old/v2 && list start work)"
+
+# Tab index
+cd "$TEST_ROOT/start"
+check "--index picks a ranked match" "$TEST_ROOT/start/work/api/old/v2" "$("$BIN" work api v2 --index 1 --quiet)"
+check "a numeric last term is a search term" "$TEST_ROOT/start/logs/2024" "$("$BIN" logs 2024 --quiet)"
+check "a numeric term is not a match index" "" "$(list archive 1)"
+check "--index still picks the second match" "$TEST_ROOT/start/archive-old" "$("$BIN" archive --index 1 --quiet)"
+check "--index works with a numeric term" "$TEST_ROOT/start/release/2024" "$("$BIN" release 2024 --index 0 --quiet)"
+if ! "$BIN" work --index nope --quiet 2>/dev/null; then
+    echo -e "${GREEN}✓ PASSED${NC}: --index rejects non-numbers"
+else
+    echo -e "${RED}✗ FAILED${NC}: --index rejects non-numbers"
+    FAILED=1
+fi
+
+# Visited directories outside the tree are candidates too
+"$BIN" --record "$TEST_ROOT/elsewhere/work/api/v3"
+check "visited directories anywhere match" "elsewhere/work/api/v3" "$(list work api v3)"
+check "visited directories are history matches" "History" \
+    "$("$BIN" work api v3 --json --quiet | sed 's/.*"match_quality":"\([A-Za-z]*\)".*/\1/')"
+
+# The shell wrapper passes every term through
+result=$(cd "$TEST_ROOT/start" && JCD_BINARY="$BIN" bash -c 'source "$0" >/dev/null 2>&1; jcd work api v2 && pwd' "$REPO_ROOT/jcd_function.sh")
+check "the wrapper jumps to multi-term matches" "$TEST_ROOT/start/work/api/v2" "$result"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,74 @@
+#!/bin/bash
+echo "Testing machine-readable output formats..."
+
+TEST_ROOT="/tmp/jcd_test_output"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{service,my-service,Service-api}
+
+cd "$TEST_ROOT/start"
+
+check() {
+    local description="$1"
+    local condition="$2"
+    if eval "$condition"; then
+        echo -e "${GREEN}✓ PASSED${NC}: $description"
+    else
+        echo -e "${RED}✗ FAILED${NC}: $description"
+        FAILED=1
+    fi
+}
+
+# JSON output
+json=$("$BIN" -S service --list --json --quiet)
+echo "$json"
+check "JSON emits one object per match" '[[ $(echo "$json" | wc -l) -eq 2 ]]'
+check "JSON parses and carries match metadata" \
+    'echo "$json" | python3 -c "
+import json, sys
+rows = [json.loads(line) for line in sys.stdin]
+assert rows[0][\"path\"].endswith(\"/start/service\")
+assert rows[0][\"match_quality\"] == \"ExactDown\" and rows[0][\"priority\"] == 0
+assert rows[1][\"match_quality\"] == \"PartialDown\" and rows[1][\"match_spans\"] == [[3, 10]]
+"'
+
+json=$("$BIN" -i service --index 1 --json --quiet)
+check "JSON honours the tab index and case-insensitive spans" \
+    'echo "$json" | python3 -c "
+import json, sys
+row = json.loads(sys.stdin.read())
+assert row[\"path\"].endswith(\"/start/Service-api\") and row[\"match_spans\"] == [[0, 7]]
+"'
+
+# NUL-separated and shell-quoted output for awkward directory names
+mkdir -p "$TEST_ROOT/start/$(printf 'odd\nline')" "$TEST_ROOT/start/$(printf 'odd\xffbyte')" "$TEST_ROOT/start/odd'quote"
+
+count=0
+while IFS= read -r -d '' path; do
+    [[ -d "$path" ]] && count=$((count + 1))
+done < <("$BIN" odd --list -0 --quiet)
+check "-0 output round-trips every path" '[[ $count -eq 3 ]]'
+
+count=0
+while IFS= read -r quoted; do
+    eval "path=$quoted"
+    [[ -d "$path" ]] && count=$((count + 1))
+done < <("$BIN" odd --list --shell-quote --quiet)
+check "--shell-quote output evaluates back to every path" '[[ $count -eq 3 ]]'
+
+json=$("$BIN" odd --list --json --quiet)
+check "JSON flags non-UTF-8 paths and carries their raw bytes" \
+    'echo "$json" | python3 -c "
+import json, os, sys
+rows = [json.loads(line) for line in sys.stdin]
+lossy = [row for row in rows if row.get(\"path_lossy\")]
+assert len(lossy) == 1 and \"\\ufffd\" in lossy[0][\"path\"]
+assert os.path.isdir(bytes(lossy[0][\"path_bytes\"]))
+assert all(\"path_bytes\" not in row for row in rows if row not in lossy)
+"'
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,59 @@
+#!/bin/bash
+echo "Testing the parallel directory walk (-j / JCD_THREADS / threads config)..."
+
+TEST_ROOT="/tmp/jcd_test_parallel_walk"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+# A wide tree with matches at several depths, created in scrambled order
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/{config/jcd,data}
+for a in q c x a m; do
+    for b in 3 1 2; do
+        mkdir -p "$TEST_ROOT/start/$a/$b/target-$a$b" "$TEST_ROOT/start/$a/$b/deep/$b/target"
+    done
+done
+mkdir -p "$TEST_ROOT"/start/node_modules/target
+
+# Keep the user's config, ignore file and visit history out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+echo '^node_modules$' > "$XDG_CONFIG_HOME/jcd/ignore"
+
+cd "$TEST_ROOT/start"
+
+# Lift the result and time budgets so every run sees the whole tree
+list() {
+    "$BIN" "$@" --max-results none --max-time none --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+single=$(list -j 1 target)
+check "a single thread ranks exact matches in name order" "a/1/deep/1/target a/2/deep/2/target" "$(cut -d' ' -f1-2 <<< "$single")"
+check "ignored directories stay ignored" "" "$(grep -o 'node_modules[^ ]*' <<< "$single")"
+check "matches at every depth are found" "30" "$(wc -w <<< "$single" | tr -d ' ')"
+
+# Every thread count produces the same ranking, run after run
+for threads in 2 8 0; do
+    check "-j $threads ranks like a single thread" "$single" "$(list -j "$threads" target)"
+done
+for _ in 1 2 3; do
+    check "repeated runs rank the same" "$single" "$(list --threads 16 target)"
+done
+check "multi-term queries rank the same" "$(list -j 1 deep target)" "$(list -j 8 deep target)"
+check "absolute patterns rank the same" "$(list -j 1 "$TEST_ROOT/start/target")" "$(list -j 8 "$TEST_ROOT/start/target")"
+
+# The thread count also comes from JCD_THREADS and the config file
+check "JCD_THREADS sets the thread count" "$single" "$(JCD_THREADS=4 list target)"
+echo "threads = 3" > "$XDG_CONFIG_HOME/jcd/config"
+check "the threads config key sets the thread count" "$single" "$(list target)"
+
+if ! "$BIN" -j many target --quiet >/dev/null 2>&1; then
+    echo -e "${GREEN}✓ PASSED${NC}: -j rejects non-numbers"
+else
+    echo -e "${RED}✗ FAILED${NC}: -j rejects non-numbers"
+    FAILED=1
+fi
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
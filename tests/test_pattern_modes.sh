@@ -0,0 +1,71 @@
+#!/bin/bash
+echo "Testing regex (-r) and glob (-g) pattern modes..."
+
+TEST_ROOT="/tmp/jcd_test_pattern_modes"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{svc-users-api,svc-orders-api,svc-web,api-gateway,Svc-Admin-api}
+mkdir -p "$TEST_ROOT"/svc-top-api/inner
+mkdir -p "$TEST_ROOT"/proj/{a1,b2,cc}/{src,docs}
+mkdir -p "$TEST_ROOT"/start/{"[archive]","[old","2*3"}
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+quality() {
+    "$BIN" "$@" --json --quiet | sed 's/.*"match_quality":"\([A-Za-z]*\)".*/\1/'
+}
+
+# Glob mode
+check "glob is detected automatically" "start/Svc-Admin-api start/svc-orders-api start/svc-users-api" "$(list 'svc-*-api')"
+check "-g forces glob mode" "start/svc-orders-api start/svc-users-api" "$(list -g -S 'svc-*-api')"
+check "glob pinned at both ends is exact" "ExactDown" "$(quality 'svc-*-api')"
+check "glob with trailing * is a prefix match" "PrefixDown" "$(quality 'svc-w*')"
+check "glob with leading * is a partial match" "PartialDown" "$(quality '*-gateway')"
+check "glob honors -i" "start/Svc-Admin-api" "$(list -i 'SVC-A*')"
+check "glob character classes" "start/svc-users-api" "$(list 'svc-[u]*')"
+
+# Names that only look like globs fall back to a literal search
+check "glob that finds nothing is searched literally" "start/[archive]" "$(list '[archive]')"
+check "invalid glob is searched literally" "start/[old" "$(list '[old')"
+check "-F matches literally" "start/2*3" "$(list -F '2*3')"
+check "-F skips a glob that would match" "" "$(list -F 'svc-*-api')"
+check "-g keeps a glob that finds nothing" "" "$(list -g '[archive]')"
+
+# Regex mode
+check "regex matches anywhere in the name" "start/api-gateway" "$(list -r 'gate')"
+check "regex anchored at the start is a prefix match" "PrefixDown" "$(quality -r '^svc-u')"
+check "regex covering the name is exact" "ExactDown" "$(quality -r '^svc-w[a-z]+$')"
+check "regex alternation" "start/svc-web start/svc-users-api" "$(list -r '^svc-(users|web)')"
+check "regex spans cover the match" '[[4,7]]' \
+    "$("$BIN" -r 'w.b' --json --quiet | sed 's/.*"match_spans":\(.*\)}/\1/')"
+
+if ! "$BIN" -r '(' --quiet 2>/dev/null; then
+    echo -e "${GREEN}✓ PASSED${NC}: invalid regex is rejected"
+else
+    echo -e "${RED}✗ FAILED${NC}: invalid regex should fail"
+    FAILED=1
+fi
+
+# Up the tree and path segments
+cd "$TEST_ROOT/svc-top-api/inner"
+check "regex matches up the tree" "svc-top-api" "$(list -r 'top-a')"
+check "glob matches up the tree" "svc-top-api" "$(list 'svc-t*')"
+
+cd "$TEST_ROOT"
+check "regex in each path segment" "proj/a1/src proj/b2/src" "$(list -r 'proj/[ab][0-9]/src')"
+check "glob in each path segment" "proj/a1/src" "$(list 'proj/?1/s*')"
+
+# Path patterns rank their last segment like a single name
+check "path pattern prefix is a prefix match" "PrefixDown" "$(quality 'proj/a1/sr')"
+check "path pattern glob with trailing * is a prefix match" "PrefixDown" "$(quality 'proj/?1/s*')"
+check "path pattern exact name is exact" "ExactDown" "$(quality 'proj/a1/docs')"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
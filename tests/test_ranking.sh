@@ -0,0 +1,64 @@
+#!/bin/bash
+echo "Testing the configurable ranking model (rank.* config keys)..."
+
+TEST_ROOT="/tmp/jcd_test_ranking"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/target/start/{target-api,old-logs,x/y/logs,a/.hidden/proj,b/proj,c/PROJ,d/e/proj,f/myproj}
+mkdir -p "$TEST_ROOT"/{config/jcd,data}
+
+# Keep the user's config and visit history out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+CONFIG="$XDG_CONFIG_HOME/jcd/config"
+
+cd "$TEST_ROOT/target/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/||; s|^target/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+# The built-in ordering: prefix children before exact ancestors
+check "prefix children beat exact ancestors by default" "target-api target" "$(list target)"
+check "JSON reports the default bucket" '"priority":6' "$("$BIN" target --index 1 --json --quiet | grep -o '"priority":[0-9]*')"
+
+# Quality and direction buckets
+echo "rank.exact-parent = 0  # ancestors first" > "$CONFIG"
+check "rank.exact-parent moves exact ancestors first" "target target-api" "$(list target)"
+check "JSON reports the configured bucket" '"priority":0' "$("$BIN" target --json --quiet | grep -o '"priority":[0-9]*')"
+
+# Penalties for hidden directories, depth and path length
+: > "$CONFIG"
+check "hidden directories are not penalized by default" "b/proj a/.hidden/proj d/e/proj f/myproj" "$(list proj -S)"
+echo "rank.hidden = 1" > "$CONFIG"
+check "rank.hidden pushes hidden directories down" "b/proj d/e/proj a/.hidden/proj f/myproj" "$(list proj -S)"
+echo "rank.depth = 6" > "$CONFIG"
+check "rank.depth lets shallow partial matches beat deep exact ones" "b/proj f/myproj a/.hidden/proj d/e/proj" "$(list proj -S)"
+echo "rank.path-length = 6" > "$CONFIG"
+check "rank.path-length pushes longer paths down" "b/proj f/myproj a/.hidden/proj d/e/proj" "$(list proj -S)"
+
+# Tie-breakers: case-exact spellings first by default, or the shallowest match first
+: > "$CONFIG"
+check "score breaks ties before depth by default" "b/proj a/.hidden/proj d/e/proj c/PROJ f/myproj" "$(list -i proj)"
+echo "rank.tiebreak = depth" > "$CONFIG"
+check "rank.tiebreak puts the listed keys first" "b/proj c/PROJ a/.hidden/proj d/e/proj f/myproj" "$(list -i proj)"
+
+# Recency: visit history lifts matches unless its weight is zero
+: > "$CONFIG"
+check "closest match wins without history" "old-logs x/y/logs" "$(list logs)"
+for _ in $(seq 1 20); do
+    "$BIN" --record x/y/logs
+done
+check "visit history lifts a deeper match" "x/y/logs old-logs" "$(list logs)"
+echo "rank.recency = 0" > "$CONFIG"
+check "rank.recency = 0 ignores visit history" "old-logs x/y/logs" "$(list logs)"
+
+# Bad settings are ignored
+printf 'rank.exact-parent = soon\nrank.bogus = 1\nrank.tiebreak = depth, nonsense\n' > "$CONFIG"
+check "invalid ranking settings keep the defaults" "target-api target" "$(list target)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -28,12 +28,12 @@ ls -d /da* 2>/dev/null || echo "No directories found starting with 'da' in root"
 
 echo
 echo "Testing timing for '/da' pattern (should be fast - immediate matches):"
-time ./target/release/jcd "/da" 0 2>/dev/null
+time ./target/release/jcd "/da" --index 0 2>/dev/null
 echo
 
 echo "Getting all matches for '/da':"
 for i in {0..3}; do
-    result=$(./target/release/jcd "/da" $i 2>/dev/null)
+    result=$(./target/release/jcd "/da" --index $i 2>/dev/null)
     if [ $? -eq 0 ]; then
         echo "  Match $i: $result"
     else
@@ -55,7 +55,7 @@ if [ -d "/datadrive2" ]; then
 
     echo
     echo "Testing '/datadrive2/un' (first match should start with 'un'):"
-    result=$(./target/release/jcd "/datadrive2/un" 0 2>/dev/null)
+    result=$(./target/release/jcd "/datadrive2/un" --index 0 2>/dev/null)
     if [ $? -eq 0 ]; then
         basename_result=$(basename "$result")
         if [[ "$basename_result" =~ ^un ]]; then
@@ -81,7 +81,7 @@ mkdir -p /tmp/jcd_regression_test/{immediate1,immediate2,deep/nested}
 cd /tmp/jcd_regression_test
 
 echo "Testing relative pattern 'imm' (should find immediate matches quickly):"
-time ../datadrive/jcd/target/release/jcd "imm" 0 2>/dev/null
+time ../datadrive/jcd/target/release/jcd "imm" --index 0 2>/dev/null
 echo
 
 echo "Testing relative pattern '../' navigation:"
@@ -81,7 +81,7 @@ test_jcd "Search for 'foo' from grandparent level" \
 
 # Test 5: Multi-match relative search
 test_jcd "Find all matches for '../ch' pattern" \
-         "/datadrive/jcd/target/release/jcd '../ch' 0" \
+         "/datadrive/jcd/target/release/jcd '../ch' --index 0" \
          ".*/child[12]$"
 
 # Test shell function
@@ -0,0 +1,49 @@
+#!/bin/bash
+echo "Testing 'jcd init <shell>' shell integration output..."
+
+TEST_ROOT="/tmp/jcd_test_init"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+BIN="$(realpath "$BIN")"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/initdest
+
+check() {
+    local description="$1"
+    local condition="$2"
+    if eval "$condition"; then
+        echo -e "${GREEN}✓ PASSED${NC}: $description"
+    else
+        echo -e "${RED}✗ FAILED${NC}: $description"
+        FAILED=1
+    fi
+}
+
+script=$("$BIN" init bash)
+check "init bash succeeds" '[[ -n "$script" ]]'
+check "binary path is filled in" 'grep -q "local jcd_binary='"'$BIN'"'" <<<"$script"'
+check "no JCD_BINARY default remains" '! grep -q "JCD_BINARY:-" <<<"$script"'
+check "init zsh succeeds" '"$BIN" init zsh >/dev/null'
+check "flag list matches the sourced script" \
+    '[[ "$(grep "^_JCD_SEARCH_FLAGS=" <<<"$script")" == "$(grep "^_JCD_SEARCH_FLAGS=" "$REPO_ROOT/jcd_function.sh")" ]]'
+check "flag list includes long flags" 'grep -q "^_JCD_SEARCH_FLAGS=(.* --fuzzy .* --fixed)" <<<"$script"'
+check "unsupported shell is rejected" '! "$BIN" init tcsh >/dev/null 2>&1'
+
+fish_script=$("$BIN" init fish)
+check "init fish succeeds" '[[ -n "$fish_script" ]]'
+check "fish script calls the binary for completions" 'grep -q "'"'$BIN'"' \$flags \$cur --list -0" <<<"$fish_script"'
+check "fish completions pass -i and -x through" 'grep -q "complete -c jcd -s i" <<<"$fish_script" && grep -q "complete -c jcd -s x" <<<"$fish_script"'
+check "fish completes long flags" 'grep -q "complete -c jcd -s f -l fuzzy" <<<"$fish_script" && grep -q "complete -c jcd -l no-acronym" <<<"$fish_script"'
+check "fish passes long flags through" 'grep -q "contains -- \$token .* --case-sensitive .* --fixed" <<<"$fish_script"'
+if command -v fish >/dev/null 2>&1; then
+    check "fish script parses" 'fish -n -c "$fish_script"'
+fi
+
+# The generated function must work without JCD_BINARY set
+result=$(cd "$TEST_ROOT/start" && env -u JCD_BINARY bash -c 'eval "$("$0" init bash)" 2>/dev/null; jcd initdest && pwd' "$BIN")
+check "generated jcd function changes directory" '[[ "$result" == "$TEST_ROOT/start/initdest" ]]'
+
+echo -e "\nCleaning up..."
+rm -rf "$TEST_ROOT"
+exit $FAILED
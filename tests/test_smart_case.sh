@@ -0,0 +1,65 @@
+#!/bin/bash
+echo "Testing smart case (-s / -S / -i and the case config key)..."
+
+TEST_ROOT="/tmp/jcd_test_smart_case"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{Docs,docs-old,README.d,v1-Build}
+mkdir -p "$TEST_ROOT"/Workspace/app/src
+mkdir -p "$TEST_ROOT"/config/jcd
+
+# Keep the user's config file out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+# Smart case is the default
+check "lowercase patterns ignore case" "start/Docs start/docs-old" "$(list docs)"
+check "an uppercase letter makes the pattern case-sensitive" "start/Docs" "$(list Docs)"
+check "case-exact names win ties" "start/docs-old start/Docs" "$(list doc)"
+check "-s is the default policy" "start/Docs start/docs-old" "$(list -s docs)"
+check "--smart-case is an alias for -s" "start/Docs" "$(list --smart-case Docs)"
+
+# Explicit policies
+check "-S matches case exactly" "start/docs-old" "$(list -S docs)"
+check "--case-sensitive is an alias for -S" "start/docs-old" "$(list --case-sensitive docs)"
+check "-i ignores case even with uppercase letters" "start/Docs start/docs-old" "$(list -i DOCS)"
+check "the last case flag wins" "start/docs-old" "$(list -i -S docs)"
+
+# Every comparison path follows the policy
+check "regex escapes are not uppercase letters" "start/v1-Build" "$(list -r 'v\d-build')"
+check "regex uppercase letters are case-sensitive" "" "$(list -r 'V\d')"
+check "globs follow smart case" "start/README.d" "$(list 'read*')"
+check "path patterns follow smart case" "Workspace/app/src" "$(cd "$TEST_ROOT" && list work/src)"
+check "path patterns with uppercase stay case-sensitive" "" "$(cd "$TEST_ROOT" && list WORK/src)"
+check "parents match up the tree ignoring case" "Workspace" "$(cd "$TEST_ROOT/Workspace/app/src" && list workspace)"
+
+# Tab completion of "../name" asks the binary, so it agrees with the jump
+export JCD_BINARY="$BIN"
+source "$REPO_ROOT/jcd_function.sh"
+completions() {
+    (cd "$TEST_ROOT/start/Docs" && _jcd_get_relative_matches "$@" | tr '\0' '\n' | sed "s|$TEST_ROOT/||" | tr '\n' ' ' | sed 's/ $//')
+}
+check "../ completion follows smart case" "start/Docs" "$(completions ../Doc)"
+check "../ completion ignores case for lowercase patterns" "start/docs-old start/Docs" "$(completions ../doc)"
+check "../ completion honours -S" "start/docs-old" "$(completions ../doc -S)"
+check "../ completion honours -i" "start/Docs start/docs-old" "$(completions ../DOC -i)"
+
+# The default comes from the config file, and flags override it
+echo "case = sensitive" > "$XDG_CONFIG_HOME/jcd/config"
+check "config can make matching case-sensitive" "start/docs-old" "$(list docs)"
+check "flags override the config" "start/Docs start/docs-old" "$(list -s docs)"
+printf '# comment\n\ncase = insensitive\n' > "$XDG_CONFIG_HOME/jcd/config"
+check "config can make matching case-insensitive" "start/Docs start/docs-old" "$(list DOCS)"
+echo "case = shouting" > "$XDG_CONFIG_HOME/jcd/config"
+check "unknown policies fall back to smart case" "start/Docs" "$(list Docs)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
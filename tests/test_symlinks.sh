@@ -0,0 +1,43 @@
+#!/bin/bash
+echo "Testing directory detection for files and symlinks..."
+
+TEST_ROOT="/tmp/jcd_test_symlinks"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/{config,data,cache} "$TEST_ROOT"/start/projects/real/widgets "$TEST_ROOT"/outside/gadgets
+touch "$TEST_ROOT"/start/projects/widgets-notes "$TEST_ROOT"/start/gadgets-file
+ln -s "$TEST_ROOT"/outside "$TEST_ROOT"/start/projects/shortcut
+ln -s "$TEST_ROOT"/start/projects "$TEST_ROOT"/start/projects/real/loop
+ln -s "$TEST_ROOT"/start/projects/widgets-notes "$TEST_ROOT"/start/notes-link
+ln -s "$TEST_ROOT"/missing "$TEST_ROOT"/start/dangling
+
+# Keep the user's config and visit history out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+export XDG_DATA_HOME="$TEST_ROOT/data"
+export XDG_CACHE_HOME="$TEST_ROOT/cache"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+check "files are not matched" "projects/real/widgets" "$(list widgets)"
+check "symlinks to directories are matched" "projects/shortcut" "$(list shortcut)"
+check "symlinks to directories are not searched below" "" "$(list gadgets)"
+check "symlinks back up the tree are matched once" "projects/real/loop" "$(list -j 4 loop)"
+check "path patterns do not cross symlinks" "" "$(list shortcut/gadgets)"
+check "symlinks to files are skipped" "" "$(list notes-link)"
+check "dangling symlinks are skipped" "" "$(list dangling)"
+
+# The index remembers which subdirectories are symlinks
+"$BIN" index build >/dev/null
+check "the index lists symlinks to directories" "projects/shortcut" "$(list shortcut)"
+check "the index does not search below symlinks" "" "$(list gadgets)"
+check "the index matches symlinks back up the tree once" "projects/real/loop" "$(list loop)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,58 @@
+#!/bin/bash
+echo "Testing the typo fallback when nothing matches..."
+
+TEST_ROOT="/tmp/jcd_test_typo"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{service,containers,contain,api,docs}/deep
+mkdir -p "$TEST_ROOT"/data
+
+# Keep the user's visit history out of the way
+export XDG_DATA_HOME="$TEST_ROOT/data"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+quality() {
+    "$BIN" "$@" --json --quiet | sed 's/.*"match_quality":"\([A-Za-z]*\)".*/\1/'
+}
+
+# Every kind of single edit is forgiven
+check "swapped letters" "service" "$(list serivce)"
+check "a missing letter" "service" "$(list servce)"
+check "an extra letter" "service" "$(list servicce)"
+check "a wrong letter" "service" "$(list servise)"
+check "typo matches are their own tier" "Typo" "$(quality serivce)"
+
+# Longer patterns allow more edits, and closer names come first
+check "closer names rank first" "containers contain" "$(list containrs)"
+check "short patterns are not corrected" "" "$(list apx)"
+check "too many edits is no match" "" "$(list srevcie)"
+
+# The fallback only runs when nothing else matched
+check "real matches suppress typos" "service" "$(list servic)"
+check "typos follow the case policy" "" "$(list SERIVCE)"
+check "-i typos ignore case" "service" "$(list -i SERIVCE)"
+check "regex patterns are not corrected" "" "$(list -r serivce)"
+
+# Parents count as well
+check "typos match up the tree" "Typo" "$(cd service/deep && quality jcd_test_tpyo)"
+
+# The jump says what it guessed on stderr
+hint=$("$BIN" serivce 2>&1 >/dev/null)
+check "a hint names the guessed directory" "jcd: no match for 'serivce', did you mean 'service'?" "$hint"
+check "--quiet leaves the hint out" "" "$("$BIN" serivce --quiet 2>&1 >/dev/null)"
+check "--list leaves the hint out" "" "$("$BIN" serivce --list 2>&1 >/dev/null)"
+
+# The shell wrapper follows the guess
+result=$(cd "$TEST_ROOT/start" && JCD_BINARY="$BIN" bash -c 'source "$0" >/dev/null 2>&1; jcd serivce 2>/dev/null && pwd' "$REPO_ROOT/jcd_function.sh")
+check "the wrapper jumps to the closest name" "$TEST_ROOT/start/service" "$result"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
@@ -0,0 +1,69 @@
+#!/bin/bash
+echo "Testing Unicode normalization (-u / -a and the normalize config key)..."
+
+TEST_ROOT="/tmp/jcd_test_unicode"
+source "$(dirname "${BASH_SOURCE[0]}")/lib.sh"
+
+# Directory names are built from explicit bytes so NFC and NFD spellings stay distinct
+NFC_CAFE=$(printf 'Caf\xc3\xa9')         # Café, precomposed é
+NFD_CAFE=$(printf 'Cafe\xcc\x81-nfd')    # Café-nfd, e + combining acute
+STRASSE=$(printf 'Stra\xc3\x9fe')        # Straße
+RESUME=$(printf 'r\xc3\xa9sum\xc3\xa9')  # résumé
+
+rm -rf "$TEST_ROOT"
+mkdir -p "$TEST_ROOT"/start/{"$NFC_CAFE","$NFD_CAFE","$STRASSE","$RESUME"-docs,plain}
+mkdir -p "$TEST_ROOT"/config/jcd
+
+# Keep the user's config file out of the way
+export XDG_CONFIG_HOME="$TEST_ROOT/config"
+
+cd "$TEST_ROOT/start"
+
+list() {
+    "$BIN" "$@" --list --quiet 2>/dev/null | sed "s|$TEST_ROOT/start/||" | tr '\n' ' ' | sed 's/ $//'
+}
+
+quality() {
+    "$BIN" "$@" --json --quiet | sed 's/.*"match_quality":"\([A-Za-z]*\)".*/\1/'
+}
+
+spans() {
+    "$BIN" "$@" --json --quiet | sed 's/.*"match_spans":\(.*\)}/\1/'
+}
+
+# Normalization is off by default
+check "NFC and NFD spellings differ by default" "$NFC_CAFE" "$(list "$(printf 'caf\xc3\xa9')")"
+check "accents matter by default" "" "$(list resume)"
+
+# -u: canonical decomposition and full case folding
+check "-u matches NFC and NFD spellings" "$NFC_CAFE $NFD_CAFE" "$(list -u "$(printf 'caf\xc3\xa9')")"
+check "--unicode is an alias for -u" "$NFC_CAFE $NFD_CAFE" "$(list --unicode "$(printf 'cafe\xcc\x81')")"
+check "-u applies full case folding" "$STRASSE" "$(list -u strasse)"
+check "-u keeps accents significant" "" "$(list -u resume)"
+check "-u still follows smart case" "" "$(list -u STRASSE)"
+check "--no-unicode turns it off again" "" "$(list -u --no-unicode strasse)"
+
+# -a: accents are ignored as well
+check "-a ignores accents" "$RESUME-docs" "$(list -a resume)"
+check "--ignore-accents is an alias for -a" "$NFC_CAFE $NFD_CAFE" "$(list --ignore-accents cafe)"
+check "-a exact matches rank as exact" "ExactDown" "$(quality -a cafe)"
+check "-a works with globs" "$RESUME-docs" "$(list -a 'resume-*')"
+check "-a works with regexes" "$RESUME-docs" "$(list -a -r '^resume')"
+check "-a works with fuzzy matching" "$RESUME-docs" "$(list -a -f rsmdcs)"
+
+# Spans refer to the name as stored on disk
+check "spans cover the original precomposed bytes" "[[0,5]]" "$(spans -a cafe --index 0)"
+check "spans cover the original decomposed bytes" "[[0,6]]" "$(spans -u "$(printf 'caf\xc3\xa9')" --index 1)"
+check "spans cover case-folded expansions" "[[0,7]]" "$(spans -u strasse)"
+
+# The default comes from the config file, and flags override it
+echo "normalize = ignore-accents" > "$XDG_CONFIG_HOME/jcd/config"
+check "config can enable accent-insensitive matching" "$RESUME-docs" "$(list resume)"
+check "flags override the config" "" "$(list --no-unicode resume)"
+echo "normalize = unicode" > "$XDG_CONFIG_HOME/jcd/config"
+check "config can enable Unicode normalization" "$STRASSE" "$(list strasse)"
+
+echo -e "\nCleaning up..."
+cd /
+rm -rf "$TEST_ROOT"
+exit $FAILED
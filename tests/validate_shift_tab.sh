@@ -19,7 +19,7 @@ export -f jcd >/dev/null 2>&1 || true
 
 # Test that the JCD binary works
 echo "Testing JCD binary:"
-"$JCD_BINARY" test1 0 2>/dev/null && echo "✓ Binary works" || echo "✗ Binary failed"
+"$JCD_BINARY" test1 --index 0 2>/dev/null && echo "✓ Binary works" || echo "✗ Binary failed"
 
 # Check that the shell functions contain the new direction variable
 echo "Checking for Shift+Tab implementation:"
@@ -15,7 +15,7 @@ def run_jcd(pattern, index=0, cwd=None, case_sensitive=True):
     cmd = ['/datadrive/jcd/target/release/jcd']
     if not case_sensitive:  # Add -i flag for case insensitive
         cmd.append('-i')
-    cmd.extend([pattern, str(index)])
+    cmd.extend([pattern, '--index', str(index)])
     try:
         result = subprocess.run(cmd, capture_output=True, text=True, cwd=cwd, timeout=5)
         if result.returncode == 0:
@@ -111,7 +111,7 @@ def test_basic_functionality():
 def test_case_sensitivity():
     """Test case sensitivity functionality with -i flag."""
     print("\n=== Case Sensitivity Test ===")
-    print("Default: case sensitive, -i flag: case insensitive")
+    print("Default: smart case, -i flag: case insensitive")
 
     # Create test structure with different case directories
     test_dir = Path("/tmp/jcd_case_test")
@@ -130,9 +130,9 @@ def test_case_sensitivity():
     # Test case sensitivity
     tests = [
         ("test", False, ["TestDir", "testdir", "TESTDIR"], "Case insensitive 'test' with -i"),
-        ("TestDir", True, ["TestDir"], "Case sensitive 'TestDir' (default)"),
-        ("testdir", True, ["testdir"], "Case sensitive 'testdir' (default)"),
-        ("TESTDIR", True, ["TESTDIR"], "Case sensitive 'TESTDIR' (default)"),
+        ("TestDir", True, ["TestDir"], "Smart case 'TestDir' is case sensitive (default)"),
+        ("testdir", True, ["testdir"], "Smart case 'testdir' prefers the exact spelling (default)"),
+        ("TESTDIR", True, ["TESTDIR"], "Smart case 'TESTDIR' is case sensitive (default)"),
     ]
 
     for pattern, case_sensitive, possible_matches, description in tests: